@@ -0,0 +1,188 @@
+use std::{
+    hint::black_box,
+    io::{self, BufReader, Cursor, Read},
+    thread,
+    time::Duration,
+};
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use sha3sum::{Mode, Sponge};
+
+// a large input runs the per-block absorb loop thousands of times, so any allocation made inside
+// that loop shows up directly in these numbers
+fn absorb(c: &mut Criterion) {
+    let data = vec![0xa5; 1 << 20];
+
+    let mut group = c.benchmark_group("absorb");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    group.bench_function("absorb_reader 1 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            sponge.absorb_reader(Cursor::new(black_box(&data))).unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    group.bench_function("absorb_reader_double_buffered 1 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            sponge
+                .absorb_reader_double_buffered(Cursor::new(black_box(&data)))
+                .unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    group.finish();
+}
+
+// stands in for a disk or network filesystem: every read waits before returning its data
+struct SlowReader<R> {
+    inner: R,
+    latency: Duration,
+}
+
+impl<R: Read> Read for SlowReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        thread::sleep(self.latency);
+        self.inner.read(buffer)
+    }
+}
+
+// with reads as slow as hashing, reading ahead on a second thread should come close to halving
+// the time taken
+fn slow_reader(c: &mut Criterion) {
+    let data = vec![0xa5; 4 << 20];
+
+    let mut group = c.benchmark_group("slow_reader");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.sample_size(10);
+
+    let reader = || SlowReader {
+        inner: Cursor::new(&data),
+        latency: Duration::from_micros(200),
+    };
+
+    group.bench_function("absorb_reader 4 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            let reader = BufReader::with_capacity(sponge.buffer_size(), reader());
+            sponge.absorb_reader(reader).unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    group.bench_function("absorb_reader_double_buffered 4 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            sponge.absorb_reader_double_buffered(reader()).unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    group.finish();
+}
+
+// one-shot hashing throughput for each SHA-3 mode: 1 KiB is dominated by setup and the final
+// padded block, 64 MiB by the permutation alone
+fn absorb_bytes(c: &mut Criterion) {
+    const SIZES: [(&str, usize); 3] =
+        [("1 KiB", 1 << 10), ("1 MiB", 1 << 20), ("64 MiB", 64 << 20)];
+    let data = vec![0xa5; 64 << 20];
+
+    let mut group = c.benchmark_group("absorb_bytes");
+
+    for (label, size) in SIZES {
+        group.throughput(Throughput::Bytes(size as u64));
+        // keeps the 64 MiB runs to a few seconds per mode
+        group.sample_size(if size >= 64 << 20 { 10 } else { 100 });
+
+        for mode in [
+            Mode::Sha3_224,
+            Mode::Sha3_256,
+            Mode::Sha3_384,
+            Mode::Sha3_512,
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(mode.to_string(), label),
+                &size,
+                |b, &size| {
+                    b.iter(|| {
+                        let mut sponge = Sponge::new(mode);
+                        sponge.absorb_bytes(black_box(&data[..size]));
+                        sponge.squeeze_bytes()
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// the cost of one block: the bare permutation at 24 and 12 rounds, and a full SHA3-256 block fed
+// through `update`, which adds xoring the block into the state
+fn permutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permutation");
+    let mut state = [0u64; 25];
+
+    group.bench_function("keccak_f1600", |b| {
+        b.iter(|| sha3sum::keccak_f1600(black_box(&mut state)))
+    });
+
+    group.bench_function("keccak_p1600 12 rounds", |b| {
+        b.iter(|| sha3sum::keccak_p1600(black_box(&mut state), 12))
+    });
+
+    let block = [0xa5; 136];
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    group.bench_function("update one block", |b| {
+        b.iter(|| sponge.update(black_box(&block)))
+    });
+
+    group.finish();
+}
+
+// hashes a file through `BufReader` and, with the `mmap` feature, through a memory map; the file
+// is written once up front and stays in the page cache, so this measures copying, not the disk
+fn file(c: &mut Criterion) {
+    let data = vec![0xa5; 64 << 20];
+    let path = std::env::temp_dir().join("sha3sum-bench-64MiB");
+    std::fs::write(&path, &data).unwrap();
+
+    let mut group = c.benchmark_group("file");
+    group.throughput(Throughput::Bytes(data.len() as u64));
+    group.sample_size(20);
+
+    let name = path.to_string_lossy().into_owned();
+    group.bench_function("absorb 64 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            sponge.absorb(black_box(&name)).unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    #[cfg(feature = "mmap")]
+    group.bench_function("absorb_mmap 64 MiB", |b| {
+        b.iter(|| {
+            let mut sponge = Sponge::new(Mode::Sha3_256);
+            sponge.absorb_mmap(black_box(&path)).unwrap();
+            sponge.squeeze_bytes()
+        })
+    });
+
+    group.finish();
+    std::fs::remove_file(&path).unwrap();
+}
+
+criterion_group!(
+    benches,
+    permutation,
+    absorb,
+    slow_reader,
+    absorb_bytes,
+    file
+);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+/* Links against the static library and checks a few digests; run it with `make -C ffi`. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "sha3sum.h"
+
+static int failures = 0;
+
+static void check(const char *what, int ok) {
+    if (!ok) {
+        printf("FAILED: %s\n", what);
+        failures++;
+    }
+}
+
+static void to_hex(const uint8_t *bytes, size_t len, char *out) {
+    for (size_t i = 0; i < len; i++) {
+        sprintf(out + 2 * i, "%02x", bytes[i]);
+    }
+}
+
+/* hashes `message` in `mode`, one byte at a time so update is called many times, and compares
+   the digest with `expected` */
+static void check_digest(const char *mode, const char *message, size_t out_len,
+                         const char *expected) {
+    uint8_t digest[64];
+    char hex[129];
+
+    Sha3Sponge *sponge = sha3_new(mode);
+    check(mode, sponge != NULL);
+    if (sponge == NULL) {
+        return;
+    }
+
+    for (size_t i = 0; i < strlen(message); i++) {
+        check("sha3_update", sha3_update(sponge, (const uint8_t *)message + i, 1) == 0);
+    }
+    check("sha3_finalize", sha3_finalize(sponge, digest, out_len) == 0);
+    to_hex(digest, out_len, hex);
+    check(mode, strcmp(hex, expected) == 0);
+
+    /* finalize resets the sponge, so hashing the same message again gives the same digest */
+    check("sha3_update", sha3_update(sponge, (const uint8_t *)message, strlen(message)) == 0);
+    check("sha3_finalize", sha3_finalize(sponge, digest, out_len) == 0);
+    to_hex(digest, out_len, hex);
+    check("digest after reset", strcmp(hex, expected) == 0);
+
+    sha3_free(sponge);
+}
+
+int main(void) {
+    check_digest("sha3-256", "abc", 32,
+                 "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532");
+    check_digest("512", "", 64,
+                 "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6"
+                 "15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26");
+    check_digest("shake128", "", 16, "7f9c2ba4e88f827d616045507605853e");
+
+    uint8_t digest[32];
+    Sha3Sponge *sponge = sha3_new("sha3-256");
+    check("digest length", sha3_digest_len(sponge) == 32);
+    check("null data with a length", sha3_update(sponge, NULL, 1) == -1);
+    check("null data without a length", sha3_update(sponge, NULL, 0) == 0);
+    check("wrong output length", sha3_finalize(sponge, digest, 31) == -1);
+    check("null output", sha3_finalize(sponge, NULL, 32) == -1);
+    sha3_free(sponge);
+
+    check("unknown mode", sha3_new("md5") == NULL);
+    check("null mode", sha3_new(NULL) == NULL);
+    check("null sponge length", sha3_digest_len(NULL) == 0);
+    check("null sponge update", sha3_update(NULL, digest, 1) == -1);
+    check("null sponge finalize", sha3_finalize(NULL, digest, 32) == -1);
+    sha3_free(NULL);
+
+    if (failures == 0) {
+        printf("all FFI checks passed\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
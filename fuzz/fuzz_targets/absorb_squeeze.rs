@@ -0,0 +1,37 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sha3sum::Sponge;
+
+#[path = "../../tests/common/reference.rs"]
+mod reference;
+
+use reference::{MODES, reference};
+
+// hashes the input in every mode, once in one piece and once through `update` split at a point
+// taken from the input itself, and checks both against the reference
+fuzz_target!(|data: &[u8]| {
+    let split = data
+        .first()
+        .map_or(0, |&byte| byte as usize)
+        .min(data.len());
+
+    for mode in MODES {
+        let expected = reference(mode, data);
+
+        let mut sponge = Sponge::new(mode);
+        sponge.absorb_bytes(data);
+        assert_eq!(sponge.squeeze_bytes(), expected, "absorb_bytes in {}", mode);
+
+        let mut sponge = Sponge::new(mode);
+        sponge.update(&data[..split]);
+        sponge.update(&data[split..]);
+        assert_eq!(
+            sponge.finalize(),
+            expected,
+            "update split at {} in {}",
+            split,
+            mode
+        );
+    }
+});
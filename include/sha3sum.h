@@ -0,0 +1,60 @@
+#ifndef SHA3SUM_H
+#define SHA3SUM_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A sponge owned by C code, created with [`sha3_new`] and released with [`sha3_free`].
+typedef struct Sha3Sponge Sha3Sponge;
+
+// Creates a sponge for `mode`, named as on the command line (`"sha3-256"`, `"256"`,
+// `"shake128"`, ...). Returns null if `mode` is null, not UTF-8, or not a known mode.
+//
+// # Safety
+//
+// `mode` must be null or point to a NUL-terminated string.
+struct Sha3Sponge *sha3_new(const char *mode);
+
+// The digest length of the sponge's mode in bytes, or 0 if `sponge` is null. SHAKE modes
+// can produce any length; this is their default.
+//
+// # Safety
+//
+// `sponge` must be null or a pointer returned by [`sha3_new`] and not yet freed.
+size_t sha3_digest_len(const struct Sha3Sponge *sponge);
+
+// Absorbs `len` bytes from `data`. Returns 0 on success and -1 if `sponge` is null, or
+// `data` is null while `len` is not 0.
+//
+// # Safety
+//
+// `sponge` must be null or a live pointer from [`sha3_new`], and `data` must be null or
+// valid for reads of `len` bytes.
+int sha3_update(struct Sha3Sponge *sponge, const uint8_t *data, size_t len);
+
+// Pads the input absorbed so far, writes `out_len` bytes of digest to `out`, and resets
+// the sponge so it can hash another message. `out_len` must be [`sha3_digest_len`] for
+// the fixed-length modes; SHAKE modes accept any length.
+//
+// Returns 0 on success and -1 if `sponge` or `out` is null or `out_len` is wrong for the
+// mode, in which case the sponge is left as it was.
+//
+// # Safety
+//
+// `sponge` must be null or a live pointer from [`sha3_new`], and `out` must be null or
+// valid for writes of `out_len` bytes.
+int sha3_finalize(struct Sha3Sponge *sponge, uint8_t *out, size_t out_len);
+
+// Frees a sponge from [`sha3_new`]. Null is ignored.
+//
+// # Safety
+//
+// `sponge` must be null or a pointer from [`sha3_new`] that has not been freed already.
+void sha3_free(struct Sha3Sponge *sponge);
+
+#endif  /* SHA3SUM_H */
@@ -0,0 +1,114 @@
+use alloc::{format, vec::Vec};
+
+use crate::{Mode, Sha3Error, Sponge};
+
+/// Collects the settings for a [`Sponge`] in one place: the mode, the output length of an
+/// XOF, the read size, and the cSHAKE function name and customization string. Anything not
+/// set keeps the default of [`Sponge::new`], and the mode defaults to [`Mode::default`].
+///
+/// ```
+/// use sha3sum::{Mode, SpongeBuilder};
+///
+/// let mut sponge = SpongeBuilder::new()
+///     .mode(Mode::CShake128)
+///     .output_len(64)
+///     .customization(b"email signature")
+///     .build()
+///     .unwrap();
+/// sponge.update(b"hello");
+/// assert_eq!(sponge.finalize().len(), 64);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SpongeBuilder {
+    mode: Mode,
+    output_len: Option<usize>,
+    buffer_size: Option<usize>,
+    function_name: Vec<u8>,
+    customization: Vec<u8>,
+    #[cfg(feature = "zeroize")]
+    zeroize: Option<bool>,
+}
+
+impl SpongeBuilder {
+    pub fn new() -> Self {
+        SpongeBuilder::default()
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The number of bytes [`Sponge::finalize`] and [`Sponge::squeeze_bytes`] return. Only
+    /// the SHAKE, cSHAKE and RawSHAKE modes have a choice of length.
+    pub fn output_len(mut self, len: usize) -> Self {
+        self.output_len = Some(len);
+        self
+    }
+
+    /// See [`Sponge::with_buffer_size`].
+    pub fn buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = Some(size);
+        self
+    }
+
+    /// The cSHAKE function name `N`, reserved for functions NIST defines on top of it.
+    pub fn function_name(mut self, name: &[u8]) -> Self {
+        self.function_name = name.to_vec();
+        self
+    }
+
+    /// The cSHAKE customization string `S`.
+    pub fn customization(mut self, customization: &[u8]) -> Self {
+        self.customization = customization.to_vec();
+        self
+    }
+
+    /// Whether the sponge wipes its state when dropped, on by default. Turning it off saves
+    /// the wipe for sponges that never see secrets.
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize(mut self, zeroize: bool) -> Self {
+        self.zeroize = Some(zeroize);
+        self
+    }
+
+    /// Fails if an output length was set for a mode with a fixed digest length, or a function
+    /// name or customization string for a mode other than cSHAKE.
+    pub fn build(self) -> Result<Sponge, Sha3Error> {
+        let mut sponge = if self.function_name.is_empty() && self.customization.is_empty() {
+            Sponge::new(self.mode)
+        } else {
+            Sponge::new_cshake(self.mode, &self.function_name, &self.customization)?
+        };
+
+        if let Some(len) = self.output_len {
+            if !sponge.xof {
+                return Err(Sha3Error::InvalidParameters(format!(
+                    "{} always has a {}-byte digest, its output length cannot be set",
+                    self.mode,
+                    self.mode.output_len()
+                )));
+            }
+
+            sponge.output_len = len;
+        }
+
+        if let Some(size) = self.buffer_size {
+            sponge = sponge.with_buffer_size(size);
+        }
+
+        #[cfg(feature = "zeroize")]
+        if let Some(zeroize) = self.zeroize {
+            sponge.zeroize = zeroize;
+        }
+
+        Ok(sponge)
+    }
+}
+
+impl Sponge {
+    /// Starts a [`SpongeBuilder`].
+    pub fn builder() -> SpongeBuilder {
+        SpongeBuilder::new()
+    }
+}
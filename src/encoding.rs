@@ -0,0 +1,68 @@
+use alloc::string::String;
+
+const STANDARD_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const LOWER_HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const UPPER_HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+// two digits per byte, high nibble first, into one string sized up front
+fn encode_hex(bytes: &[u8], digits: &[u8; 16]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 2);
+
+    for &byte in bytes {
+        encoded.push(digits[(byte >> 4) as usize] as char);
+        encoded.push(digits[(byte & 0x0f) as usize] as char);
+    }
+
+    encoded
+}
+
+/// Lowercase hex, two digits per byte.
+pub fn to_hex(bytes: &[u8]) -> String {
+    encode_hex(bytes, LOWER_HEX_DIGITS)
+}
+
+/// Uppercase hex, two digits per byte.
+pub fn to_hex_upper(bytes: &[u8]) -> String {
+    encode_hex(bytes, UPPER_HEX_DIGITS)
+}
+
+// maps every 3 input bytes to 4 symbols, the last group short and optionally '='-padded
+fn encode(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for group in bytes.chunks(3) {
+        let mut word = [0; 3];
+        word[..group.len()].copy_from_slice(group);
+        let bits = u32::from_be_bytes([0, word[0], word[1], word[2]]);
+
+        // n bytes carry 8n bits, which takes n + 1 six-bit symbols
+        for symbol in 0..group.len() + 1 {
+            let index = (bits >> (18 - 6 * symbol)) & 0x3f;
+            encoded.push(alphabet[index as usize] as char);
+        }
+
+        if pad {
+            for _ in group.len()..3 {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Standard, `=`-padded Base64 (RFC 4648 section 4).
+pub fn to_base64(bytes: &[u8]) -> String {
+    encode(bytes, STANDARD_ALPHABET, true)
+}
+
+/// URL- and filename-safe Base64 without padding (RFC 4648 section 5).
+pub fn to_base64_url(bytes: &[u8]) -> String {
+    encode(bytes, URL_SAFE_ALPHABET, false)
+}
@@ -0,0 +1,127 @@
+use alloc::boxed::Box;
+use core::{
+    ffi::{CStr, c_char, c_int},
+    slice,
+};
+
+use crate::{Mode, Sponge};
+
+/// A sponge owned by C code, created with [`sha3_new`] and released with [`sha3_free`].
+pub struct Sha3Sponge {
+    sponge: Sponge,
+}
+
+/// Creates a sponge for `mode`, named as on the command line (`"sha3-256"`, `"256"`,
+/// `"shake128"`, ...). Returns null if `mode` is null, not UTF-8, or not a known mode.
+///
+/// # Safety
+///
+/// `mode` must be null or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sha3_new(mode: *const c_char) -> *mut Sha3Sponge {
+    if mode.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    // SAFETY: the caller passes a NUL-terminated string
+    let name = unsafe { CStr::from_ptr(mode) };
+    match name.to_str().map(Mode::try_from) {
+        Ok(Ok(mode)) => Box::into_raw(Box::new(Sha3Sponge {
+            sponge: Sponge::new(mode),
+        })),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// The digest length of the sponge's mode in bytes, or 0 if `sponge` is null. SHAKE modes
+/// can produce any length; this is their default.
+///
+/// # Safety
+///
+/// `sponge` must be null or a pointer returned by [`sha3_new`] and not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sha3_digest_len(sponge: *const Sha3Sponge) -> usize {
+    // SAFETY: the caller passes a live sponge or null
+    match unsafe { sponge.as_ref() } {
+        Some(sponge) => sponge.sponge.output_len,
+        None => 0,
+    }
+}
+
+/// Absorbs `len` bytes from `data`. Returns 0 on success and -1 if `sponge` is null, or
+/// `data` is null while `len` is not 0.
+///
+/// # Safety
+///
+/// `sponge` must be null or a live pointer from [`sha3_new`], and `data` must be null or
+/// valid for reads of `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sha3_update(
+    sponge: *mut Sha3Sponge,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    // SAFETY: the caller passes a live sponge or null
+    let Some(sponge) = (unsafe { sponge.as_mut() }) else {
+        return -1;
+    };
+
+    if len == 0 {
+        return 0;
+    }
+    if data.is_null() {
+        return -1;
+    }
+
+    // SAFETY: the caller guarantees `len` readable bytes at `data`
+    sponge
+        .sponge
+        .update(unsafe { slice::from_raw_parts(data, len) });
+    0
+}
+
+/// Pads the input absorbed so far, writes `out_len` bytes of digest to `out`, and resets
+/// the sponge so it can hash another message. `out_len` must be [`sha3_digest_len`] for
+/// the fixed-length modes; SHAKE modes accept any length.
+///
+/// Returns 0 on success and -1 if `sponge` or `out` is null or `out_len` is wrong for the
+/// mode, in which case the sponge is left as it was.
+///
+/// # Safety
+///
+/// `sponge` must be null or a live pointer from [`sha3_new`], and `out` must be null or
+/// valid for writes of `out_len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sha3_finalize(
+    sponge: *mut Sha3Sponge,
+    out: *mut u8,
+    out_len: usize,
+) -> c_int {
+    // SAFETY: the caller passes a live sponge or null
+    let Some(sponge) = (unsafe { sponge.as_mut() }) else {
+        return -1;
+    };
+    let sponge = &mut sponge.sponge;
+
+    if out.is_null() || (!sponge.xof && out_len != sponge.output_len) {
+        return -1;
+    }
+
+    // SAFETY: the caller guarantees `out_len` writable bytes at `out`
+    let out = unsafe { slice::from_raw_parts_mut(out, out_len) };
+    sponge.finalize_and_reset(out);
+    0
+}
+
+/// Frees a sponge from [`sha3_new`]. Null is ignored.
+///
+/// # Safety
+///
+/// `sponge` must be null or a pointer from [`sha3_new`] that has not been freed already.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sha3_free(sponge: *mut Sha3Sponge) {
+    if !sponge.is_null() {
+        // SAFETY: the pointer came from Box::into_raw in sha3_new and is freed only once
+        drop(unsafe { Box::from_raw(sponge) });
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::vec::Vec;
+
+use crate::{Mode, Sponge, hash_bytes};
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+// `key`, or its digest when longer than a block, zero-padded to a block and xored with `pad`
+fn padded_key(mode: Mode, key: &[u8], pad: u8) -> Vec<u8> {
+    let mut block = if key.len() > mode.rate() {
+        hash_bytes(mode, key)
+    } else {
+        key.to_vec()
+    };
+    block.resize(mode.rate(), 0);
+    block.iter_mut().for_each(|byte| *byte ^= pad);
+    block
+}
+
+/// HMAC from RFC 2104 over `mode`, with the rate as the block size: the digest of
+/// `(key ^ opad) || H((key ^ ipad) || data)`. Keys longer than a block are hashed first.
+/// HMAC is only standardized for the SHA-3 modes; the others get the same construction at
+/// their default output length.
+pub fn hmac(mode: Mode, key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut inner = Sponge::new(mode);
+    inner.update(&padded_key(mode, key, IPAD));
+    inner.update(data);
+
+    let mut outer = Sponge::new(mode);
+    outer.update(&padded_key(mode, key, OPAD));
+    outer.update(&inner.finalize());
+    outer.finalize()
+}
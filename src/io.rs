@@ -0,0 +1,254 @@
+#[cfg(feature = "mmap")]
+use std::path::Path;
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    sync::mpsc,
+    thread,
+};
+
+use crate::{Sha3Error, Sponge};
+
+/// Tells the OS that `file` is about to be read from start to end, so it can read ahead more
+/// aggressively. Uses `posix_fadvise` on Linux and Android and does nothing elsewhere; the
+/// advice is only a hint, so failures are ignored.
+pub fn advise_sequential(file: &File) {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        use std::os::fd::AsRawFd;
+
+        // SAFETY: the descriptor is open for as long as `file` is borrowed, and the call
+        // only changes the kernel's read-ahead for it
+        unsafe {
+            libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL);
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let _ = file;
+}
+
+impl Sponge {
+    pub fn absorb(&mut self, file_path: &String) -> Result<(), Sha3Error> {
+        if file_path.is_empty() {
+            return Err(Sha3Error::EmptyInput);
+        }
+        self.ensure_not_finalized()?;
+
+        // padding is driven by how much was actually read, so pipes and FIFOs (whose metadata
+        // reports no length) hash the same as regular files; only per-block counts are kept, so
+        // files larger than `usize::MAX` bytes are fine on 32-bit targets too
+        let file_handle = File::open(file_path)?;
+        advise_sequential(&file_handle);
+        self.absorb_reader(BufReader::with_capacity(self.read_buffer_size, file_handle))
+    }
+
+    /// Absorbs the file at `file_path` through a read-only memory map, hashing it in place
+    /// rather than copying it through a read buffer first. Worth it for large files; for
+    /// small ones setting up the mapping costs more than it saves.
+    ///
+    /// The file is hashed at the length it had when it was opened. Mapped pages past the
+    /// end of a file that shrinks cannot be read, so the length is checked again before
+    /// every stretch of blocks and a truncated file fails with an [`Sha3Error::Io`] of kind
+    /// [`std::io::ErrorKind::UnexpectedEof`]. Truncation in the middle of a stretch can still
+    /// crash the process with `SIGBUS`, so only map files nothing else is rewriting.
+    #[cfg(feature = "mmap")]
+    pub fn absorb_mmap(&mut self, file_path: &Path) -> Result<(), Sha3Error> {
+        // blocks absorbed between two checks of the file length, about 1 MiB of input
+        const BLOCKS_PER_CHECK: usize = 8 * 1024;
+
+        self.ensure_not_finalized()?;
+
+        let file_handle = File::open(file_path)?;
+        let len = file_handle.metadata()?.len();
+        let truncated = || {
+            std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "file was truncated while being hashed",
+            )
+        };
+
+        // an empty mapping is an error on some platforms, and there is nothing to map anyway
+        if len == 0 {
+            self.absorb_padding();
+            return Ok(());
+        }
+
+        // SAFETY: the map is only read while the file length is at least the mapped length,
+        // see the doc comment for the window that leaves
+        let map = unsafe {
+            memmap2::MmapOptions::new()
+                .len(
+                    usize::try_from(len)
+                        .map_err(|_| std::io::Error::from(std::io::ErrorKind::FileTooLarge))?,
+                )
+                .map(&file_handle)?
+        };
+
+        // only a hint to read further ahead, so a kernel that refuses it changes nothing
+        #[cfg(unix)]
+        let _ = map.advise(memmap2::Advice::Sequential);
+
+        // anything `update` buffered before the call is absorbed ahead of the file
+        for stretch in map.chunks(BLOCKS_PER_CHECK * self.bit_rate) {
+            if file_handle.metadata()?.len() < len {
+                return Err(truncated().into());
+            }
+
+            self.update(stretch);
+        }
+
+        self.absorb_padding();
+        Ok(())
+    }
+
+    // reads until `buffer` is full or the reader is exhausted, returning the byte count
+    fn read_block<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let mut bytes_read = 0;
+
+        while bytes_read < buffer.len() {
+            match reader.read(&mut buffer[bytes_read..]) {
+                Ok(0) => break,
+                Ok(count) => bytes_read += count,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(bytes_read)
+    }
+
+    pub fn absorb_reader<R: Read>(&mut self, reader: R) -> Result<(), Sha3Error> {
+        self.absorb_reader_with_progress(reader, |_| {})
+    }
+
+    /// Like [`Sponge::absorb_reader`], but calls `progress` after every block with the total
+    /// number of bytes absorbed so far, the last call coming once the whole input is in. Lets
+    /// a caller show how far hashing has got without wrapping the reader.
+    pub fn absorb_reader_with_progress<R: Read>(
+        &mut self,
+        mut reader: R,
+        mut progress: impl FnMut(u64),
+    ) -> Result<(), Sha3Error> {
+        self.ensure_not_finalized()?;
+
+        // one block buffer serves the whole input, with anything `update` buffered before the
+        // call absorbed ahead of it
+        let mut buffer = vec![0; self.bit_rate];
+        let mut absorbed = 0;
+
+        loop {
+            let bytes_read = Sponge::read_block(&mut reader, &mut buffer)?;
+            self.update(&buffer[..bytes_read]);
+            absorbed += bytes_read as u64;
+            progress(absorbed);
+
+            // a short block means the reader is exhausted; a full one is never taken as the
+            // last, so input that is an exact multiple of the rate gets a block of pure padding
+            if bytes_read < buffer.len() {
+                break;
+            }
+        }
+
+        self.absorb_padding();
+        Ok(())
+    }
+
+    /// Like [`Sponge::absorb_reader`], but hashes no more than the first `limit` bytes of
+    /// `reader`, leaving the rest unread. A reader that runs out sooner is hashed whole.
+    pub fn absorb_reader_limited<R: Read>(
+        &mut self,
+        reader: R,
+        limit: u64,
+    ) -> Result<(), Sha3Error> {
+        self.absorb_reader(reader.take(limit))
+    }
+
+    /// Absorbs each of `readers` in turn as one input, as if their contents had been joined
+    /// into a single stream. Nothing marks where one ends and the next begins, so the digest
+    /// depends on their order, and `["ab", "c"]` hashes the same as `["a", "bc"]`.
+    pub fn absorb_readers<R: Read>(
+        &mut self,
+        readers: impl IntoIterator<Item = R>,
+    ) -> Result<(), Sha3Error> {
+        self.ensure_not_finalized()?;
+
+        for mut reader in readers {
+            std::io::copy(&mut reader, self)?;
+        }
+
+        self.absorb_padding();
+        Ok(())
+    }
+
+    /// Like [`Sponge::absorb_reader`], but reads on a second thread, so the next chunk of
+    /// input is being read while the current one is hashed. This hides read latency on
+    /// spinning disks and network filesystems. Chunks are [`Sponge::buffer_size`] bytes, with
+    /// two of them in flight, and each is hashed in one piece, so not every read has to be a
+    /// whole block.
+    pub fn absorb_reader_double_buffered<R: Read + Send>(
+        &mut self,
+        mut reader: R,
+    ) -> Result<(), Sha3Error> {
+        self.ensure_not_finalized()?;
+
+        let chunk_size = self.read_buffer_size;
+        let (filled_sender, filled) = mpsc::sync_channel::<std::io::Result<(Vec<u8>, usize)>>(1);
+        let (recycle, empty) = mpsc::channel();
+        for _ in 0..2 {
+            recycle.send(vec![0; chunk_size]).unwrap();
+        }
+
+        thread::scope(|scope| {
+            // the reader stops after a short chunk or an error, or once the hashing side has
+            // hung up; it waits on `empty` while both chunks are out
+            scope.spawn(move || {
+                for mut chunk in empty {
+                    let result = Sponge::read_block(&mut reader, &mut chunk);
+                    let last = !matches!(result, Ok(count) if count == chunk_size);
+
+                    if filled_sender
+                        .send(result.map(|count| (chunk, count)))
+                        .is_err()
+                        || last
+                    {
+                        break;
+                    }
+                }
+            });
+
+            // moving `recycle` in here drops it on every way out, which lets the reader finish
+            // before the scope joins it
+            let recycle = recycle;
+            for message in &filled {
+                let (chunk, count) = message?;
+                self.update(&chunk[..count]);
+
+                if count < chunk_size {
+                    break;
+                }
+
+                // the reader is gone only once it has sent its last chunk, so this can't fail
+                let _ = recycle.send(chunk);
+            }
+
+            Ok::<(), Sha3Error>(())
+        })?;
+
+        self.absorb_padding();
+        Ok(())
+    }
+}
+
+// lets a sponge be the sink of io::copy; call finalize afterwards for the digest. Writing to a
+// finalized sponge fails with `Sha3Error::AlreadyFinalized` inside the `io::Error`
+impl std::io::Write for Sponge {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.try_update(buf).map_err(std::io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
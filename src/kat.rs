@@ -0,0 +1,205 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{Mode, Sponge, hash_bytes};
+
+const MODES: [Mode; 4] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+];
+
+// the SHA3-224, -256, -384 and -512 digests of each message in `messages`, in that order; the
+// messages are the ones NIST publishes examples for alongside FIPS 202
+const DIGESTS: [[&str; 4]; 5] = [
+    [
+        "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+        "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+    ],
+    [
+        "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+    ],
+    [
+        "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33",
+        "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+        "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+        "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+    ],
+    [
+        "543e6868e1666c1a643630df77367ae5a62a85070a51c14cbf665cbc",
+        "916f6061fe879741ca6469b43971dfdb28b1a32dc36cb3254e812be27aad1d18",
+        "79407d3b5916b59c3e30b09822974791c313fb9ecc849e406f23592d04f625dc8c709b98b43b3852b337216179aa7fc7",
+        "afebb2ef542e6579c50cad06d2e578f9f8dd6881d7dc824d26360feebf18a4fa73e3261122948efcfd492e74e82e2189ed0fb440d187f382270cb455f21dd185",
+    ],
+    [
+        "d69335b93325192e516a912e6d19a15cb51c6ed5c15243e7a7fd653c",
+        "5c8875ae474a3634ba4fd55ec85bffd661f32aca75c6d699d0cdcb6c115891c1",
+        "eee9e24d78c1855337983451df97c8ad9eedf256c6334f8e948d252d5e0e76847aa0774ddb90a842190d2c558b4b8340",
+        "3c3a876da14034ab60627c077bb98f7e120a2a5370212dffb3385a18d4f38859ed311d0a9d5141ce9cc5c66ee689b266a8aa18ace8282a0e0db596c90b0a7b87",
+    ],
+];
+
+// the empty message, "abc", the 448- and 896-bit alphabet strings, and a million 'a's; between
+// them they hit a final block that is empty, short, and several blocks into the input
+fn messages() -> [Vec<u8>; 5] {
+    [
+        Vec::new(),
+        b"abc".to_vec(),
+        b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+        b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu".to_vec(),
+        vec![b'a'; 1_000_000],
+    ]
+}
+
+// short messages in hex with their digests: per mode, the first 8-bit message of the NIST CAVP
+// byte-oriented ShortMsg file, then messages one byte short of the rate, exactly the rate and
+// one byte over it, taken from the byte-aligned short-message KATs the Keccak team published
+// for SHA-3. The padding byte lands at the end of a block, in a block of its own, and one byte
+// into the next
+const SHORT_MESSAGES: [(Mode, &str, &str); 16] = [
+    (
+        Mode::Sha3_224,
+        "01",
+        "488286d9d32716e5881ea1ee51f36d3660d70f0db03b3f612ce9eda4",
+    ),
+    (
+        Mode::Sha3_224,
+        "ea40e83cb18b3a242c1ecc6ccd0b7853a439dab2c569cfc6dc38a19f5c90acbf76aef9ea3742ff3b54ef7d36eb7ce4ff1c9ab3bc119cff6be93c03e208783335c0ab8137be5b10cdc66ff3f89a1bddc6a1eed74f504cbe7290690bb295a872b9e3fe2cee9e6c67c41db8efd7d863cf10f840fe618e7936da3dca5ca6df933f24f6954ba0801a1294cd8d7e66dfafec",
+        "ab0fd308590574d6f6130232d9fafa9ffcfea78579a6a8f67c590420",
+    ),
+    (
+        Mode::Sha3_224,
+        "157d5b7e4507f66d9a267476d33831e7bb768d4d04cc3438da12f9010263ea5fcafbde2579db2f6b58f911d593d5f79fb05fe3596e3fa80ff2f761d1b0e57080055c118c53e53cdb63055261d7c9b2b39bd90acc32520cbbdbda2c4fd8856dbcee173132a2679198daf83007a9b5c51511ae49766c792a29520388444ebefe28256fb33d4260439cba73a9479ee00c63",
+        "d5134200dc98f4ca480cd24d24497737252b55977ae5a869ba27089d",
+    ),
+    (
+        Mode::Sha3_224,
+        "836b34b515476f613fe447a4e0c3f3b8f20910ac89a3977055c960d2d5d2b72bd8acc715a9035321b86703a411dde0466d58a59769672aa60ad587b8481de4bba552a1645779789501ec53d540b904821f32b0bd1855b04e4848f9f8cfe9ebd8911be95781a759d7ad9724a7102dbe576776b7c632bc39b9b5e19057e226552a5994c1dbb3b5c7871a11f5537011044c53",
+        "494cbc9b649e48ec5ad7364aeb9c8edf4a4f400789ef203f7b818a44",
+    ),
+    (
+        Mode::Sha3_256,
+        "e9",
+        "f0d04dd1e6cfc29a4460d521796852f25d9ef8d28b44ee91ff5b759d72c1e6d6",
+    ),
+    (
+        Mode::Sha3_256,
+        "b771d5cef5d1a41a93d15643d7181d2a2ef0a8e84d91812f20ed21f147bef732bf3a60ef4067c3734b85bc8cd471780f10dc9e8291b58339a677b960218f71e793f2797aea349406512829065d37bb55ea796fa4f56fd8896b49b2cd19b43215ad967c712b24e5032d065232e02c127409d2ed4146b9d75d763d52db98d949d3b0fed6a8052fbb",
+        "a19eee92bb2097b64e823d597798aa18be9b7c736b8059abfd6779ac35ac81b5",
+    ),
+    (
+        Mode::Sha3_256,
+        "b32d95b0b9aad2a8816de6d06d1f86008505bd8c14124f6e9a163b5a2ade55f835d0ec3880ef50700d3b25e42cc0af050ccd1be5e555b23087e04d7bf9813622780c7313a1954f8740b6ee2d3f71f768dd417f520482bd3a08d4f222b4ee9dbd015447b33507dd50f3ab4247c5de9a8abd62a8decea01e3b87c8b927f5b08beb37674c6f8e380c04",
+        "df673f4105379ff6b755eeab20ceb0dc77b5286364fe16c59cc8a907aff07732",
+    ),
+    (
+        Mode::Sha3_256,
+        "04410e31082a47584b406f051398a6abe74e4da59bb6f85e6b49e8a1f7f2ca00dfba5462c2cd2bfde8b64fb21d70c083f11318b56a52d03b81cac5eec29eb31bd0078b6156786da3d6d8c33098c5c47bb67ac64db14165af65b44544d806dde5f487d5373c7f9792c299e9686b7e5821e7c8e2458315b996b5677d926dac57b3f22da873c601016a0d",
+        "d52432cf3b6b4b949aa848e058dcd62d735e0177279222e7ac0af8504762faa0",
+    ),
+    (
+        Mode::Sha3_384,
+        "80",
+        "7541384852e10ff10d5fb6a7213a4a6c15ccc86d8bc1068ac04f69277142944f4ee50d91fdc56553db06b2f5039c8ab7",
+    ),
+    (
+        Mode::Sha3_384,
+        "f13c972c52cb3cc4a4df28c97f2df11ce089b815466be88863243eb318c2adb1a417cb1041308598541720197b9b1cb5ba2318bd5574d1df2174af14884149ba9b2f446d609df240ce335599957b8ec80876d9a085ae084907bc5961b20bf5f6ca58d5dab38adb",
+        "0a834e111b4e840e787c19748465a47d88b3f0f3daaf15db25536bdc6078fa9c05e6c953830274223968847da8bfd20d",
+    ),
+    (
+        Mode::Sha3_384,
+        "e35780eb9799ad4c77535d4ddb683cf33ef367715327cf4c4a58ed9cbdcdd486f669f80189d549a9364fa82a51a52654ec721bb3aab95dceb4a86a6afa93826db923517e928f33e3fba850d45660ef83b9876accafa2a9987a254b137c6e140a21691e1069413848",
+        "d1c0fa85c8d183beff99ad9d752b263e286b477f79f0710b010317017397813344b99daf3bb7b1bc5e8d722bac85943a",
+    ),
+    (
+        Mode::Sha3_384,
+        "64ec021c9585e01ffe6d31bb50d44c79b6993d72678163db474947a053674619d158016adb243f5c8d50aa92f50ab36e579ff2dabb780a2b529370daa299207cfbcdd3a9a25006d19c4f1fe33e4b1eaec315d8c6ee1e730623fd1941875b924eb57d6d0c2edc4e78d6",
+        "6aedcf4426b2483c0d0d04695bcc052bedd04fa4d17a1bbb2797f6272fa476bfc138e4091409feb1ac0e8bff350a6663",
+    ),
+    (
+        Mode::Sha3_512,
+        "e5",
+        "150240baf95fb36f8ccb87a19a41767e7aed95125075a2b2dbba6e565e1ce8575f2b042b62e29a04e9440314a821c6224182964d8b557b16a492b3806f4c39c1",
+    ),
+    (
+        Mode::Sha3_512,
+        "13bd2811f6ed2b6f04ff3895aceed7bef8dcd45eb121791bc194a0f806206bffc3b9281c2b308b1a729ce008119dd3066e9378acdcc50a98a82e20738800b6cddbe5fe9694ad6d",
+        "def4ab6cda8839729a03e000846604b17f03c5d5d7ec23c483670a13e11573c1e9347a63ec69a5abb21305f9382ecdaaabc6850f92840e86f88f4dabfcd93cc0",
+    ),
+    (
+        Mode::Sha3_512,
+        "1eed9cba179a009ec2ec5508773dd305477ca117e6d569e66b5f64c6bc64801ce25a8424ce4a26d575b8a6fb10ead3fd1992edddeec2ebe7150dc98f63adc3237ef57b91397aa8a7",
+        "a3e168b0d6c143ee9e17eae92930b97e6600356b73aebb5d68005dd1d07494451a37052f7b39ff030c1ae1d7efc4e0c3667eb7a76c627ec14354c4f6a796e2c6",
+    ),
+    (
+        Mode::Sha3_512,
+        "ba5b67b5ec3a3ffae2c19dd8176a2ef75c0cd903725d45c9cb7009a900c0b0ca7a2967a95ae68269a6dbf8466c7b6844a1d608ac661f7eff00538e323db5f2c644b78b2d48de1a08aa",
+        "635741b37f66cd5ce4dbd1f78accd907f96146e770b239046afb9181910b612d0e65841ff866806eed83c3ae7012fc55e42c3ffc9c6e3d03ce2870442f293ab4",
+    ),
+];
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+        .collect()
+}
+
+/// Checks all four SHA-3 modes against the known-answer vectors, panicking on the first
+/// digest that does not match. Each message is hashed both in one piece and fed through
+/// [`Sponge::update`] in uneven pieces, so block boundaries fall in different places.
+pub fn run_test() {
+    for (mode, message, expected) in SHORT_MESSAGES {
+        let message = from_hex(message);
+        assert_eq!(
+            crate::to_hex(&hash_bytes(mode, &message)),
+            expected,
+            "{} of the {}-byte short message",
+            mode,
+            message.len()
+        );
+
+        let mut sponge = Sponge::new(mode);
+        for piece in message.chunks(61) {
+            sponge.update(piece);
+        }
+        assert_eq!(
+            crate::to_hex(&sponge.finalize()),
+            expected,
+            "{} of the {}-byte short message fed in pieces",
+            mode,
+            message.len()
+        );
+    }
+
+    for (message, digests) in messages().iter().zip(DIGESTS) {
+        for (mode, expected) in MODES.into_iter().zip(digests) {
+            let one_shot = crate::to_hex(&hash_bytes(mode, message));
+            assert_eq!(
+                one_shot,
+                expected,
+                "{} of a {}-byte message",
+                mode,
+                message.len()
+            );
+
+            let mut sponge = Sponge::new(mode);
+            for piece in message.chunks(61) {
+                sponge.update(piece);
+            }
+            assert_eq!(
+                crate::to_hex(&sponge.finalize()),
+                expected,
+                "{} of a {}-byte message fed in pieces",
+                mode,
+                message.len()
+            );
+        }
+    }
+}
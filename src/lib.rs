@@ -1,11 +1,72 @@
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-    os::unix::fs::MetadataExt,
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+mod builder;
+#[cfg(feature = "alloc")]
+mod encoding;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc")]
+mod hmac;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "alloc")]
+mod kat;
+#[cfg(feature = "alloc")]
+mod multi;
+#[cfg(feature = "alloc")]
+mod snapshot;
+#[cfg(feature = "alloc")]
+mod sp800_185;
+#[cfg(feature = "alloc")]
+mod turboshake;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "alloc")]
+pub use builder::SpongeBuilder;
+#[cfg(feature = "alloc")]
+pub use encoding::{to_base64, to_base64_url, to_hex, to_hex_upper};
+#[cfg(feature = "alloc")]
+pub use hmac::hmac;
+#[cfg(feature = "std")]
+pub use io::advise_sequential;
+#[cfg(feature = "alloc")]
+pub use kat::run_test;
+#[cfg(feature = "alloc")]
+pub use multi::MultiSponge;
+#[cfg(feature = "alloc")]
+pub use snapshot::SpongeState;
+
+#[cfg(feature = "alloc")]
+pub use turboshake::{k12, turboshake128, turboshake256};
+
+#[cfg(feature = "alloc")]
+pub use sp800_185::{
+    kmac128, kmac256, parallelhash128, parallelhash128_with_threads, parallelhash256,
+    parallelhash256_with_threads, tuplehash128, tuplehash256,
 };
 
-// byte-aligned suffix (01100000)
-const SHA_SUFFIX: u8 = 96;
+// cSHAKE domain bits 00 and the first padding bit (0x04), used once a function name or
+// customization string is given; the modes' own suffixes are in `Mode::suffix`
+#[cfg(feature = "alloc")]
+const CSHAKE_SUFFIX: u8 = 0x04;
+
+// read size behind `Sponge::absorb`, before it is rounded up to whole blocks, and behind
+// `MultiSponge::absorb_reader`
+const DEFAULT_READ_BUFFER_SIZE: usize = 64 * 1024;
 
 // centered array map
 const CAM: [usize; 5] = [2, 3, 4, 0, 1];
@@ -18,257 +79,796 @@ const RHO_TABLE: [[u64; 5]; 5] = [
     [15, 253, 190, 6, 171],
 ];
 
-fn static_reverse_u64_bits(number: u64) -> u64 {
-    let mut bytes = number.to_be_bytes();
-    for byte in bytes.iter_mut() {
-        let mut b = *byte;
-        let mut reversed = 0;
-        for _ in 0..8 {
-            reversed = (reversed << 1) | (b & 1);
-            b >>= 1;
+// rho rotation of lane (x, y) as RHO_OFFSETS[x][y], i.e. RHO_TABLE remapped through CAM and
+// reduced mod 64, so the permutation indexes it directly
+const RHO_OFFSETS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+// fails the build if RHO_OFFSETS ever drifts from the table it was derived from
+const _: () = {
+    let mut x = 0;
+    while x < 5 {
+        let mut y = 0;
+        while y < 5 {
+            assert!(RHO_OFFSETS[x][y] as u64 == RHO_TABLE[CAM[x]][CAM[y]] % 64);
+            y += 1;
         }
-        *byte = reversed;
+        x += 1;
     }
-
-    return u64::from_be_bytes(bytes);
-}
+};
 
 const IOTA_TABLE: [u64; 24] = [
-    9223372036854775808,
-    4684025087442026496,
-    5836946592048873473,
-    281479271677953,
-    15060318628903649280,
-    9223372041149743104,
-    9295711110164381697,
-    10376575016438333441,
-    5836665117072162816,
-    1224979098644774912,
-    10376575020733300736,
-    5764607527329202176,
-    15060318633198616576,
-    15060037153926938625,
-    10448632610476261377,
-    13835339530258874369,
-    4611967493404098561,
-    72057594037927937,
-    5764888998010945536,
-    5764607527329202177,
-    9295711110164381697,
-    72339069014638593,
-    9223372041149743104,
-    1153202983878524929,
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
 ];
 
-#[derive(Debug, Clone, Copy)]
+// runs the last `rounds` of the 24 rounds
+fn permute(state: &mut [u64; 25], rounds: usize) {
+    for &constant in &IOTA_TABLE[24 - rounds..] {
+        round(state, constant);
+    }
+}
+
+// one round with every step written out over fixed-size loops, which the compiler unrolls in full.
+// Rho and pi are fused into a single scatter, and chi writes straight back into the state. That
+// runs about 30% faster than calling a separate function per step and copying the state after
+// pi and chi, see the permutation benchmark.
+#[inline(always)]
+fn round(state: &mut [u64; 25], constant: u64) {
+    // theta
+    let mut c = [0u64; 5];
+    for x in 0..5 {
+        c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+    }
+
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            state[x + 5 * y] ^= d;
+        }
+    }
+
+    // rho and pi: lane (x, y) is rotated and moves to (y, 2x + 3y)
+    let mut moved = [0u64; 25];
+    for x in 0..5 {
+        for y in 0..5 {
+            moved[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y].rotate_left(RHO_OFFSETS[x][y]);
+        }
+    }
+
+    // chi
+    for y in 0..5 {
+        for x in 0..5 {
+            state[x + 5 * y] =
+                moved[x + 5 * y] ^ (!moved[(x + 1) % 5 + 5 * y] & moved[(x + 2) % 5 + 5 * y]);
+        }
+    }
+
+    // iota
+    state[0] ^= constant;
+}
+
+/// The Keccak-p[1600, rounds] permutation over a state of standard (little-endian bit
+/// order) lanes, indexed as `state[x + 5 * y]`. Runs the last `rounds` rounds of
+/// Keccak-f[1600], so `rounds = 12` is the permutation used by TurboSHAKE and K12.
+///
+/// Panics if `rounds` is greater than 24.
+pub fn keccak_p1600(state: &mut [u64; 25], rounds: usize) {
+    assert!(rounds <= 24, "Keccak-f[1600] has at most 24 rounds");
+    permute(state, rounds);
+}
+
+/// The full 24-round Keccak-f[1600] permutation, see [`keccak_p1600`].
+pub fn keccak_f1600(state: &mut [u64; 25]) {
+    keccak_p1600(state, 24);
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub enum Sha3Error {
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    InvalidMode(String),
+    InvalidParameters(String),
+    EmptyInput,
+    // more input after the sponge was padded, which only a reset can take
+    AlreadyFinalized,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Sha3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Sha3Error::Io(error) => write!(f, "{}", error),
+            Sha3Error::InvalidMode(mode) => write!(f, "Invalid mode selected: {}", mode),
+            Sha3Error::InvalidParameters(reason) => {
+                write!(f, "Invalid sponge parameters: {}", reason)
+            }
+            Sha3Error::EmptyInput => write!(f, "No input provided"),
+            Sha3Error::AlreadyFinalized => {
+                write!(
+                    f,
+                    "Sponge already finalized; reset it before absorbing more input"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::error::Error for Sha3Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Sha3Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Sha3Error {
+    fn from(error: std::io::Error) -> Self {
+        Sha3Error::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Mode {
-    Sha3_224(usize),
-    Sha3_256(usize),
-    Sha3_384(usize),
-    Sha3_512(usize),
+    #[default]
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    Shake128,
+    Shake256,
+    Keccak256,
+    Keccak512,
+    CShake128,
+    CShake256,
+    RawShake128,
+    RawShake256,
 }
 
-impl TryFrom<&String> for Mode {
-    type Error = &'static str;
+// every mode, in the order they are listed by `sha3sum --list`
+const ALL_MODES: [Mode; 12] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+    Mode::Shake128,
+    Mode::Shake256,
+    Mode::Keccak256,
+    Mode::Keccak512,
+    Mode::CShake128,
+    Mode::CShake256,
+    Mode::RawShake128,
+    Mode::RawShake256,
+];
 
-    fn try_from(value: &String) -> Result<Self, Self::Error> {
+/// Every supported mode, each once. Their `Display` names are the ones `Mode::try_from`
+/// accepts.
+pub fn all_modes() -> impl Iterator<Item = Mode> {
+    ALL_MODES.into_iter()
+}
+
+impl Mode {
+    /// The SHA-3 mode with a digest of `bits` bits (224, 256, 384 or 512).
+    #[cfg(feature = "alloc")]
+    pub fn from_output_bits(bits: usize) -> Result<Mode, Sha3Error> {
+        match bits {
+            224 => Ok(Mode::Sha3_224),
+            256 => Ok(Mode::Sha3_256),
+            384 => Ok(Mode::Sha3_384),
+            512 => Ok(Mode::Sha3_512),
+            _ => Err(Sha3Error::InvalidMode(format!("sha3-{}", bits))),
+        }
+    }
+
+    /// The rate in bytes: how much input is absorbed per permutation.
+    pub fn rate(&self) -> usize {
+        match self {
+            Mode::Sha3_224 => 144,
+            Mode::Sha3_256 | Mode::Keccak256 => 136,
+            Mode::Sha3_384 => 104,
+            Mode::Sha3_512 | Mode::Keccak512 => 72,
+            Mode::Shake128 | Mode::CShake128 | Mode::RawShake128 => 168,
+            Mode::Shake256 | Mode::CShake256 | Mode::RawShake256 => 136,
+        }
+    }
+
+    /// The domain suffix byte written right after the message when padding: the mode's
+    /// domain bits followed by the first padding bit, least significant bit first.
+    pub fn suffix(&self) -> u8 {
+        match self {
+            // domain bits 01
+            Mode::Sha3_224 | Mode::Sha3_256 | Mode::Sha3_384 | Mode::Sha3_512 => 0x06,
+            // domain bits 1111; cSHAKE without a function name or customization string is
+            // plain SHAKE
+            Mode::Shake128 | Mode::Shake256 | Mode::CShake128 | Mode::CShake256 => 0x1f,
+            // the original Keccak submission has no domain bits
+            Mode::Keccak256 | Mode::Keccak512 => 0x01,
+            // domain bits 11
+            Mode::RawShake128 | Mode::RawShake256 => 0x07,
+        }
+    }
+
+    /// Whether the mode is an extendable-output function, which can squeeze a digest of any
+    /// length rather than only [`Mode::output_len`] bytes.
+    pub fn is_xof(&self) -> bool {
+        matches!(
+            self,
+            Mode::Shake128
+                | Mode::Shake256
+                | Mode::CShake128
+                | Mode::CShake256
+                | Mode::RawShake128
+                | Mode::RawShake256
+        )
+    }
+
+    /// The capacity in bytes, the part of the 200-byte state never touched by input.
+    pub fn capacity(&self) -> usize {
+        200 - self.rate()
+    }
+
+    /// The digest length in bytes. For the XOF modes this is the default length used
+    /// when no explicit size is requested.
+    pub fn output_len(&self) -> usize {
+        match self {
+            Mode::Sha3_224 => 224 / 8,
+            Mode::Sha3_256 => 256 / 8,
+            Mode::Sha3_384 => 384 / 8,
+            Mode::Sha3_512 => 512 / 8,
+            Mode::Shake128 => 256 / 8,
+            Mode::Shake256 => 512 / 8,
+            Mode::Keccak256 => 256 / 8,
+            Mode::Keccak512 => 512 / 8,
+            Mode::CShake128 => 256 / 8,
+            Mode::CShake256 => 512 / 8,
+            Mode::RawShake128 => 256 / 8,
+            Mode::RawShake256 => 512 / 8,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl TryFrom<&str> for Mode {
+    type Error = Sha3Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // the SHA-3 modes may also be named by their digest size alone
         match value.to_lowercase().as_str() {
-            "224" => Ok(Self::Sha3_224(144)),
-            "256" => Ok(Self::Sha3_256(136)),
-            "384" => Ok(Self::Sha3_384(104)),
-            "512" => Ok(Self::Sha3_512(72)),
-            _ => Err("Invalide mode selected"),
+            bits @ ("224" | "256" | "384" | "512") => Mode::from_output_bits(bits.parse().unwrap()),
+            name => all_modes()
+                .find(|mode| mode.to_string() == name)
+                .ok_or_else(|| Sha3Error::InvalidMode(value.to_string())),
         }
     }
 }
 
-impl Default for Mode {
-    fn default() -> Self {
-        Mode::Sha3_224(144)
+#[cfg(feature = "alloc")]
+impl TryFrom<&String> for Mode {
+    type Error = Sha3Error;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Mode::try_from(value.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::str::FromStr for Mode {
+    type Err = Sha3Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Mode::try_from(value)
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Mode::Sha3_224 => "sha3-224",
+            Mode::Sha3_256 => "sha3-256",
+            Mode::Sha3_384 => "sha3-384",
+            Mode::Sha3_512 => "sha3-512",
+            Mode::Shake128 => "shake128",
+            Mode::Shake256 => "shake256",
+            Mode::Keccak256 => "keccak256",
+            Mode::Keccak512 => "keccak512",
+            Mode::CShake128 => "cshake128",
+            Mode::CShake256 => "cshake256",
+            Mode::RawShake128 => "rawshake128",
+            Mode::RawShake256 => "rawshake256",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// modes are written by their canonical names, as Display prints them, and read back with the
+// same parser as `-m`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Mode::try_from(name.as_str()).map_err(|_| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&name),
+                &"a mode name: sha3-224, sha3-256, sha3-384, sha3-512, shake128, shake256, \
+                  keccak256, keccak512, cshake128, cshake256, rawshake128 or rawshake256",
+            )
+        })
     }
 }
 
 pub struct Sponge {
-    mode: Mode,
-    state: [[u64; 5]; 5],
+    state: [u64; 25],
+    bit_rate: usize,
+    // digest length in bytes; XOF sponges may squeeze any length instead
+    output_len: usize,
+    xof: bool,
+    // partial block carried between update calls, of which the first `bit_rate` bytes are used
+    buffer: [u8; 200],
+    buffer_len: usize,
+    // set once the final block has been padded and absorbed, which happens only once
+    finalized: bool,
+    // domain suffix byte written when padding
+    suffix: u8,
+    // the lanes reset returns to: all zero, or the state after absorbing the cSHAKE prefix
+    initial_state: [u64; 25],
+    // permutation rounds per block, 24 except for TurboSHAKE
+    rounds: usize,
+    // capacity of the `BufReader` behind `absorb`, always a whole number of blocks
+    read_buffer_size: usize,
+    // whether dropping the sponge wipes it, see `SpongeBuilder::zeroize`
+    #[cfg(feature = "zeroize")]
+    zeroize: bool,
 }
 
 impl Sponge {
     pub fn new(mode: Mode) -> Self {
-        return Sponge {
-            mode,
-            state: [[0; 5]; 5],
-        };
+        Sponge::from_params(mode.rate(), mode.output_len(), mode.suffix(), mode.is_xof())
     }
 
-    fn reverse_bits_in_place(byte_slice: &mut [u8]) {
-        for byte in byte_slice.iter_mut() {
-            let mut b = *byte;
-            let mut reversed = 0;
-            for _ in 0..8 {
-                reversed = (reversed << 1) | (b & 1);
-                b >>= 1;
-            }
-            *byte = reversed;
+    fn from_params(bit_rate: usize, output_len: usize, suffix: u8, xof: bool) -> Self {
+        Sponge {
+            state: [0; 25],
+            bit_rate,
+            output_len,
+            xof,
+            buffer: [0; 200],
+            buffer_len: 0,
+            finalized: false,
+            suffix,
+            initial_state: [0; 25],
+            rounds: 24,
+            read_buffer_size: DEFAULT_READ_BUFFER_SIZE.div_ceil(bit_rate) * bit_rate,
+            #[cfg(feature = "zeroize")]
+            zeroize: true,
         }
     }
 
-    fn theta(&mut self) {
-        let mut c: [u64; 5] = [0; 5];
-        for x in 0..=4 {
-            c[x] = self.state[x][0]
-                ^ self.state[x][1]
-                ^ self.state[x][2]
-                ^ self.state[x][3]
-                ^ self.state[x][4];
+    /// Sets how many bytes [`Sponge::absorb`] reads from the file at a time. Larger reads
+    /// mean fewer system calls on big files; past a few hundred KiB there is little left to
+    /// gain. The size is rounded up to a whole number of blocks, so every refill hands the
+    /// sponge complete blocks.
+    ///
+    /// The default is 64 KiB rounded that way: 65664 bytes for SHA3-224, 65552 for
+    /// SHA3-256 and SHAKE256, 65624 for SHA3-384, 65592 for SHA3-512, and 65688 for
+    /// SHAKE128.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.read_buffer_size = size.max(1).div_ceil(self.bit_rate) * self.bit_rate;
+        self
+    }
+
+    /// The read size set by [`Sponge::with_buffer_size`].
+    pub fn buffer_size(&self) -> usize {
+        self.read_buffer_size
+    }
+
+    /// Creates a sponge with non-standard Keccak parameters: a rate in bytes (a multiple
+    /// of 8 below 200, the capacity being the rest of the 200-byte state), the digest
+    /// length in bytes, the domain suffix byte as written in the specifications
+    /// (`0x06` for SHA-3, `0x1f` for SHAKE, `0x01` for Keccak), and whether the sponge is
+    /// an XOF, squeezing any length the way [`Mode::is_xof`] modes do.
+    #[cfg(feature = "alloc")]
+    pub fn with_params(
+        rate_bytes: usize,
+        output_bytes: usize,
+        suffix: u8,
+        xof: bool,
+    ) -> Result<Self, Sha3Error> {
+        if rate_bytes == 0 || !rate_bytes.is_multiple_of(8) || rate_bytes >= 200 {
+            return Err(Sha3Error::InvalidParameters(format!(
+                "rate must be a non-zero multiple of 8 below 200 bytes, got {}",
+                rate_bytes
+            )));
         }
 
-        let mut d: [u64; 5] = [0; 5];
-        for x in 0..=4 {
-            d[x] = c[(x + 4) % 5] ^ (c[(x + 1) % 5].rotate_right(1));
-            for y in 0..=4 {
-                self.state[x][y] = self.state[x][y] ^ d[x];
-            }
+        if !(0x01..=0x7f).contains(&suffix) {
+            return Err(Sha3Error::InvalidParameters(format!(
+                "suffix must be in 0x01..=0x7f, got {:#04x}",
+                suffix
+            )));
         }
+
+        Ok(Sponge::from_params(rate_bytes, output_bytes, suffix, xof))
     }
 
-    fn rho(&mut self) {
-        for x in 0..=4 {
-            for y in 0..=4 {
-                self.state[x][y] = self.state[x][y].rotate_right(RHO_TABLE[CAM[x]][CAM[y]] as u32);
-            }
+    /// Creates a cSHAKE sponge (NIST SP 800-185) with a function name `N` and a
+    /// customization string `S`. With both empty this is identical to SHAKE.
+    #[cfg(feature = "alloc")]
+    pub fn new_cshake(
+        mode: Mode,
+        function_name: &[u8],
+        customization: &[u8],
+    ) -> Result<Self, Sha3Error> {
+        if !matches!(mode, Mode::CShake128 | Mode::CShake256) {
+            return Err(Sha3Error::InvalidMode(mode.to_string()));
         }
+
+        let mut sponge = Sponge::new(mode);
+        if function_name.is_empty() && customization.is_empty() {
+            return Ok(sponge);
+        }
+
+        let mut encoded = sp800_185::encode_string(function_name);
+        encoded.extend(sp800_185::encode_string(customization));
+
+        sponge.suffix = CSHAKE_SUFFIX;
+        sponge.update(&sp800_185::bytepad(&encoded, sponge.bit_rate));
+        sponge.initial_state = sponge.state;
+
+        Ok(sponge)
+    }
+
+    /// The raw lanes, indexed `x + 5 * y`, in the standard convention used by
+    /// [`keccak_f1600`]: input bytes are loaded into each lane little-endian.
+    pub fn state(&self) -> &[u64; 25] {
+        &self.state
     }
 
-    fn pi(&mut self) {
-        let mut new_state: [[u64; 5]; 5] = [[0; 5]; 5];
+    /// Overwrites the raw lanes, in the same convention as [`Sponge::state`].
+    ///
+    /// This bypasses the sponge construction entirely: any value is accepted, and the
+    /// security of the resulting digest is only as good as the state supplied.
+    pub fn set_state(&mut self, state: [u64; 25]) {
+        self.state = state;
+    }
 
-        for x in 0..=4 {
-            for y in 0..=4 {
-                new_state[x][y] = self.state[(x + (3 * y)) % 5][x]
-            }
+    /// Clears the state and any buffered input so the sponge can hash a new input.
+    /// The mode, and any cSHAKE function name and customization string, are preserved.
+    pub fn reset(&mut self) {
+        self.state = self.initial_state;
+        self.buffer.fill(0);
+        self.buffer_len = 0;
+        self.finalized = false;
+    }
+
+    /// Wipes the state, any buffered input and the lanes [`Sponge::reset`] returns to,
+    /// exactly as dropping the sponge does, for a sponge that outlives the secret it hashed.
+    /// A cSHAKE sponge loses its function name and customization string too, so build a new
+    /// one rather than hashing with it again.
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize(&mut self) {
+        use zeroize::Zeroize;
+
+        self.state.zeroize();
+        self.initial_state.zeroize();
+        self.buffer.zeroize();
+        self.buffer_len.zeroize();
+        self.finalized.zeroize();
+    }
+
+    // expects a full block of `bit_rate` bytes
+    fn absorb_block(&mut self, buffer: &[u8]) {
+        for lane in 0..(buffer.len() / 8) {
+            let slice = &buffer[(lane * 8)..((lane * 8) + 8)];
+
+            self.state[lane] ^= u64::from_le_bytes(slice.try_into().unwrap());
         }
 
-        self.state = new_state;
+        permute(&mut self.state, self.rounds);
     }
 
-    fn chi(&mut self) {
-        let mut new_state: [[u64; 5]; 5] = [[0; 5]; 5];
+    // writes the domain suffix and the closing padding bit into the final block
+    fn pad_block(&self, buffer: &mut [u8], padding_start_index: usize) {
+        let bit_rate = buffer.len();
+
+        if padding_start_index == bit_rate - 1 {
+            buffer[padding_start_index] = self.suffix | 0x80;
+        } else {
+            buffer[padding_start_index] = self.suffix;
+            buffer[bit_rate - 1] = 0x80;
+        }
+    }
 
-        for x in 0..=4 {
-            for y in 0..=4 {
-                new_state[x][y] = self.state[x][y]
-                    ^ ((!(self.state[(x + 1) % 5][y])) & self.state[(x + 2) % 5][y]);
+    /// Absorbs `data` as the end of the message and pads it, ready to squeeze. Anything
+    /// [`Sponge::update`] has buffered comes first, so `update(b"a")` followed by
+    /// `absorb_bytes(b"bc")` hashes `"abc"`.
+    ///
+    /// Panics if the sponge is already finalized, as [`Sponge::update`] does.
+    pub fn absorb_bytes(&mut self, data: &[u8]) {
+        self.update(data);
+        self.absorb_padding();
+    }
+
+    /// Absorbs `data` as more of the message, buffering any partial block until the next
+    /// call.
+    ///
+    /// Panics if the sponge is already finalized, by [`Sponge::absorb_bytes`] or a squeeze:
+    /// a padded sponge can take no more input until [`Sponge::reset`]. Use
+    /// [`Sponge::try_update`] to get [`Sha3Error::AlreadyFinalized`] instead.
+    pub fn update(&mut self, data: &[u8]) {
+        assert!(
+            !self.finalized,
+            "sponge already finalized; reset it before absorbing more input"
+        );
+
+        let bit_rate = self.bit_rate;
+        let mut data = data;
+
+        // top up a block left partly filled by the previous call first
+        if self.buffer_len > 0 {
+            let count = (bit_rate - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..(self.buffer_len + count)].copy_from_slice(&data[..count]);
+            self.buffer_len += count;
+            data = &data[count..];
+
+            if self.buffer_len < bit_rate {
+                return;
             }
+
+            let block = self.buffer;
+            self.absorb_block(&block[..bit_rate]);
+            self.buffer.fill(0);
+            self.buffer_len = 0;
+        }
+
+        // whole blocks are absorbed straight from `data`, and only the tail is buffered
+        let mut blocks = data.chunks_exact(bit_rate);
+        for block in &mut blocks {
+            self.absorb_block(block);
         }
 
-        self.state = new_state;
-    }
-
-    fn iota(&mut self, round: usize) {
-        self.state[0][0] = self.state[0][0] ^ IOTA_TABLE[round];
-    }
-
-    pub fn absorb(&mut self, file_path: &String) {
-        let file_meta = std::fs::metadata(file_path).unwrap();
-        let file_size: usize = file_meta.size().try_into().unwrap();
-
-        let file_handle = File::open(file_path).unwrap();
-        let mut file_reader = BufReader::new(file_handle);
-
-        match self.mode {
-            Mode::Sha3_224(bit_rate)
-            | Mode::Sha3_256(bit_rate)
-            | Mode::Sha3_384(bit_rate)
-            | Mode::Sha3_512(bit_rate) => {
-                let mut break_flag = false;
-
-                while !break_flag {
-                    let mut buffer = vec![0; bit_rate.try_into().unwrap()];
-                    let read_result = file_reader.read_exact(&mut buffer);
-                    Sponge::reverse_bits_in_place(&mut buffer);
-
-                    match read_result {
-                        Err(error) => match error.kind() {
-                            std::io::ErrorKind::UnexpectedEof => {
-                                let padding_start_index: usize =
-                                    (file_size % bit_rate).try_into().unwrap();
-
-                                if padding_start_index == bit_rate - 1 {
-                                    buffer[padding_start_index] = SHA_SUFFIX + 1;
-                                } else {
-                                    buffer[padding_start_index] = SHA_SUFFIX;
-                                    buffer[bit_rate - 1] = 1;
-                                }
-
-                                break_flag = true;
-                            }
-                            _ => {}
-                        },
-                        _ => {}
-                    }
-
-                    for lane in 0..(bit_rate / 8) {
-                        let x = lane % 5;
-                        let y = lane / 5;
-                        let slice = &buffer[(lane * 8)..((lane * 8) + 8)];
-
-                        self.state[x][y] =
-                            self.state[x][y] ^ u64::from_be_bytes(slice.try_into().unwrap());
-                    }
-
-                    for round in 0..=23 {
-                        self.theta();
-                        self.rho();
-                        self.pi();
-                        self.chi();
-                        self.iota(round);
-                    }
-                }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
+    }
+
+    /// Like [`Sponge::update`], but returns [`Sha3Error::AlreadyFinalized`] rather than
+    /// panicking when the sponge is already finalized.
+    #[cfg(feature = "alloc")]
+    pub fn try_update(&mut self, data: &[u8]) -> Result<(), Sha3Error> {
+        self.ensure_not_finalized()?;
+        self.update(data);
+        Ok(())
+    }
+
+    // the check behind every fallible way of absorbing input
+    #[cfg(feature = "alloc")]
+    fn ensure_not_finalized(&self) -> Result<(), Sha3Error> {
+        if self.finalized {
+            return Err(Sha3Error::AlreadyFinalized);
+        }
+
+        Ok(())
+    }
+
+    // reads output out of a copy of the state, permuting the copy after every rate-sized
+    // chunk, so the sponge itself is left undisturbed
+    fn squeeze_state(&self, out: &mut [u8]) {
+        let bit_rate = self.bit_rate;
+        let mut state = self.state;
+
+        for (block_index, block) in out.chunks_mut(bit_rate).enumerate() {
+            if block_index > 0 {
+                permute(&mut state, self.rounds);
+            }
+
+            for (lane, chunk) in block.chunks_mut(8).enumerate() {
+                chunk.copy_from_slice(&state[lane].to_le_bytes()[..chunk.len()]);
             }
         }
     }
 
+    /// Writes the digest into `out` without allocating.
+    ///
+    /// Panics if `out` is not exactly the output length of the mode. SHAKE modes accept
+    /// any length.
+    pub fn squeeze_into(&mut self, out: &mut [u8]) {
+        let output_len = self.output_len;
+        if !self.xof {
+            assert_eq!(
+                out.len(),
+                output_len,
+                "output buffer must be {} bytes for this mode",
+                output_len
+            );
+        }
+
+        // pads anything still buffered, so squeezing twice gives the same bytes
+        self.absorb_padding();
+        self.squeeze_state(out);
+    }
+
+    /// Returns `out_len` bytes of extendable output. Meant for the SHAKE modes; the
+    /// same call is repeatable and always returns a prefix of the same stream.
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_xof(&mut self, out_len: usize) -> Vec<u8> {
+        let mut output = vec![0; out_len];
+        self.absorb_padding();
+        self.squeeze_state(&mut output);
+        output
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_bytes(&mut self) -> Vec<u8> {
+        let mut output = vec![0; self.output_len];
+        self.squeeze_into(&mut output);
+        output
+    }
+
+    /// Returns the digest as a fixed-size array, e.g. `[u8; 32]` for SHA3-256.
+    ///
+    /// Panics if `N` is not the output length of the mode.
+    pub fn squeeze_array<const N: usize>(&mut self) -> [u8; N] {
+        let mut output = [0; N];
+        self.squeeze_into(&mut output);
+        output
+    }
+
+    // pads whatever update has buffered and absorbs it as the final block; a sponge that is
+    // already padded is left alone, so finalizing after absorb_bytes doesn't pad twice
+    fn absorb_padding(&mut self) {
+        if self.finalized {
+            return;
+        }
+
+        let mut block = self.buffer;
+        let block = &mut block[..self.bit_rate];
+        self.pad_block(block, self.buffer_len);
+        self.absorb_block(block);
+
+        self.buffer.fill(0);
+        self.buffer_len = 0;
+        self.finalized = true;
+    }
+
+    // finalizes into `out` (of any length) and resets, ready for the next message
+    #[cfg(any(feature = "ffi", feature = "wasm"))]
+    fn finalize_and_reset(&mut self, out: &mut [u8]) {
+        self.absorb_padding();
+        self.squeeze_state(out);
+        self.reset();
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn finalize(mut self) -> Vec<u8> {
+        self.squeeze_bytes()
+    }
+
+    /// Like [`Sponge::finalize`], but writes the digest into `out` instead of allocating.
+    ///
+    /// Panics if `out` is not exactly the output length of the mode. SHAKE modes accept
+    /// any length.
+    pub fn finalize_into(mut self, out: &mut [u8]) {
+        self.absorb_padding();
+        self.squeeze_into(out);
+    }
+
+    #[cfg(feature = "alloc")]
     pub fn squeeze(&mut self) -> String {
-        match self.mode {
-            Mode::Sha3_224(bit_rate)
-            | Mode::Sha3_256(bit_rate)
-            | Mode::Sha3_384(bit_rate)
-            | Mode::Sha3_512(bit_rate) => {
-                for x in 0..=4 {
-                    for y in 0..=4 {
-                        self.state[x][y] = static_reverse_u64_bits(self.state[x][y]);
-                    }
-                }
-
-                let mut output_hex_vec = vec![];
-                for lane in 0..(bit_rate / 8) {
-                    output_hex_vec.push(format!("{:016x}", self.state[lane % 5][lane / 5]));
-                }
-
-                let mut output_hex = output_hex_vec.join("");
-
-                match self.mode {
-                    Mode::Sha3_224(_) => {
-                        output_hex.truncate(224 / 4);
-                    }
-                    Mode::Sha3_256(_) => {
-                        output_hex.truncate(256 / 4);
-                    }
-                    Mode::Sha3_384(_) => {
-                        output_hex.truncate(384 / 4);
-                    }
-                    Mode::Sha3_512(_) => {
-                        output_hex.truncate(512 / 4);
-                    }
-                }
-
-                return output_hex;
-            }
+        to_hex(&self.squeeze_bytes())
+    }
+
+    /// Like [`Sponge::squeeze`], but with uppercase hex digits.
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_upper(&mut self) -> String {
+        to_hex_upper(&self.squeeze_bytes())
+    }
+
+    /// Returns the digest in standard, padded Base64.
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_base64(&mut self) -> String {
+        to_base64(&self.squeeze_bytes())
+    }
+
+    /// Returns the digest in URL-safe Base64 without padding.
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_base64_url(&mut self) -> String {
+        to_base64_url(&self.squeeze_bytes())
+    }
+}
+
+// wipes the state and any buffered input so key material doesn't outlive the sponge, unless
+// the builder turned that off
+#[cfg(feature = "zeroize")]
+impl Drop for Sponge {
+    fn drop(&mut self) {
+        if self.zeroize {
+            self.zeroize();
         }
     }
 }
 
-pub fn run_test() {
-    todo!();
+#[cfg(feature = "alloc")]
+fn hash_bytes(mode: Mode, data: &[u8]) -> Vec<u8> {
+    let mut sponge = Sponge::new(mode);
+    sponge.absorb_bytes(data);
+    sponge.squeeze_bytes()
+}
+
+/// Hashes `data` in one piece with the default output length of `mode`. Anything that
+/// derefs to bytes will do: `&str`, `String`, `Vec<u8>` or `&[u8]`.
+///
+/// ```
+/// use sha3sum::{Mode, digest, to_hex};
+///
+/// assert_eq!(
+///     to_hex(&digest(Mode::Sha3_256, "hello")),
+///     "3338be694f50c5f338814986cdf0686453a888b84f424d792af4b9202398f392"
+/// );
+/// assert_eq!(digest(Mode::Shake128, String::from("hello")).len(), 32);
+/// assert_eq!(digest(Mode::Sha3_512, b"hello"), sha3sum::sha3_512(b"hello"));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn digest(mode: Mode, data: impl AsRef<[u8]>) -> Vec<u8> {
+    hash_bytes(mode, data.as_ref())
+}
+
+#[cfg(feature = "alloc")]
+pub fn sha3_224(data: &[u8]) -> Vec<u8> {
+    hash_bytes(Mode::Sha3_224, data)
+}
+
+#[cfg(feature = "alloc")]
+pub fn sha3_256(data: &[u8]) -> Vec<u8> {
+    hash_bytes(Mode::Sha3_256, data)
+}
+
+#[cfg(feature = "alloc")]
+pub fn sha3_384(data: &[u8]) -> Vec<u8> {
+    hash_bytes(Mode::Sha3_384, data)
+}
+
+#[cfg(feature = "alloc")]
+pub fn sha3_512(data: &[u8]) -> Vec<u8> {
+    hash_bytes(Mode::Sha3_512, data)
 }
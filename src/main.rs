@@ -1,33 +1,1785 @@
-use std::env;
+use std::{
+    collections::BTreeMap,
+    env, fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, IsTerminal, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use sha3sum::{Mode, Sponge};
+use sha3sum::{
+    Mode, MultiSponge, Sha3Error, Sponge, all_modes, to_base64, to_base64_url, to_hex, to_hex_upper,
+};
 
-fn main() {
-    let mut mode = Mode::default();
+// io::Error's Display appends " (os error N)", which coreutils-style diagnostics omit
+fn describe_error(error: &Sha3Error) -> String {
+    let message = error.to_string();
+    match (error, message.find(" (os error")) {
+        (Sha3Error::Io(_), Some(index)) => message[..index].to_string(),
+        _ => message,
+    }
+}
+
+// writes to standard output; once whatever reads it has gone away, as in `sha3sum --list | head
+// -1`, there is no one left to tell, so that ends the run quietly and successfully, while any
+// other failure to write is reported and fails it
+fn write_out(out: &mut impl Write, output: fmt::Arguments) {
+    if let Err(error) = out.write_fmt(output).and_then(|()| out.flush()) {
+        if error.kind() == io::ErrorKind::BrokenPipe {
+            process::exit(0);
+        }
+
+        eprintln!("sha3sum: write error: {}", describe_error(&error.into()));
+        process::exit(1);
+    }
+}
+
+// hashes one named input from scratch, "-" naming standard input as with the coreutils tools
+fn hash_input(sponge: &mut Sponge, name: &String) -> Result<Vec<u8>, Sha3Error> {
+    sponge.reset();
+
+    if name == "-" {
+        sponge.absorb_reader(io::stdin().lock())?;
+    } else {
+        sponge.absorb(name)?;
+    }
+
+    Ok(sponge.squeeze_bytes())
+}
+
+// opens a named input for reading from the start of `range`, along with how many bytes of it
+// will be hashed when that is known up front
+fn open_input(
+    name: &String,
+    buffer_size: usize,
+    range: ByteRange,
+) -> Result<(Box<dyn Read>, Option<u64>), Sha3Error> {
+    let skip = range.skip;
+    if name == "-" {
+        let mut reader = io::stdin().lock();
+        discard(&mut reader, skip)?;
+        return Ok((Box::new(reader), None));
+    }
+
+    if name.is_empty() {
+        return Err(Sha3Error::EmptyInput);
+    }
+
+    let mut file_handle = File::open(name)?;
+    sha3sum::advise_sequential(&file_handle);
+    // only the part of the file that --length leaves in is read, so that is what progress counts
+    let size = match file_handle.metadata() {
+        Ok(metadata) if metadata.is_file() => Some(range.covered(metadata.len())),
+        _ => None,
+    };
+
+    // a regular file can start reading after the skipped bytes, anything else has to read
+    // through them
+    let reader = if size.is_some() {
+        file_handle.seek(SeekFrom::Start(skip))?;
+        BufReader::with_capacity(buffer_size, file_handle)
+    } else {
+        let mut reader = BufReader::with_capacity(buffer_size, file_handle);
+        discard(&mut reader, skip)?;
+        reader
+    };
+
+    Ok((Box::new(reader), size))
+}
+
+// reads and throws away the first `count` bytes of `reader`, or all of it if it is shorter
+fn discard(reader: &mut impl Read, count: u64) -> io::Result<()> {
+    io::copy(&mut reader.take(count), &mut io::sink())?;
+    Ok(())
+}
+
+// a "name: NN% (X MB/s)" line on stderr, redrawn as the sponge reports how much it has absorbed
+struct ProgressLine {
+    name: String,
+    total: u64,
+    started: Instant,
+    last_drawn: Option<Instant>,
+}
+
+impl ProgressLine {
+    // a line for `name` when progress was asked for and there is a known, nonzero size to count
+    // towards
+    fn start(wanted: bool, name: &str, size: Option<u64>) -> Option<Self> {
+        let total = size.filter(|&size| wanted && size > 0)?;
+
+        Some(ProgressLine {
+            name: name.to_string(),
+            total,
+            started: Instant::now(),
+            last_drawn: None,
+        })
+    }
+
+    fn draw(&mut self, done: u64) {
+        // redrawing on every block would cost more than the hashing
+        let now = Instant::now();
+        let finished = done >= self.total;
+        if !finished
+            && self
+                .last_drawn
+                .is_some_and(|last| now.duration_since(last) < Duration::from_millis(100))
+        {
+            return;
+        }
+        self.last_drawn = Some(now);
+
+        let percent = done.min(self.total) * 100 / self.total;
+        let seconds = now.duration_since(self.started).as_secs_f64();
+        let megabytes_per_second = if seconds > 0.0 {
+            done as f64 / 1_000_000.0 / seconds
+        } else {
+            0.0
+        };
+
+        eprint!(
+            "\r{}: {:3}% ({:.1} MB/s)",
+            self.name, percent, megabytes_per_second
+        );
+    }
+}
+
+// clears the progress line so the sum line that follows starts on a clean line
+impl Drop for ProgressLine {
+    fn drop(&mut self) {
+        if self.last_drawn.is_some() {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+// the digests of one input in the order of `modes`, with how much was hashed and how long it took
+struct Hashed {
+    digests: Vec<Vec<u8>>,
+    bytes: u64,
+    elapsed: Duration,
+}
+
+// regular files at least this large are memory-mapped instead of read, when built with `mmap`
+#[cfg(feature = "mmap")]
+const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+// the part of each input to hash, from --skip and --length
+#[derive(Clone, Copy, Default, PartialEq)]
+struct ByteRange {
+    skip: u64,
+    // unset for everything after `skip`
+    length: Option<u64>,
+}
+
+impl ByteRange {
+    // how many bytes of an input `len` bytes long get hashed
+    fn covered(&self, len: u64) -> u64 {
+        len.saturating_sub(self.skip)
+            .min(self.length.unwrap_or(u64::MAX))
+    }
+}
+
+fn digest_input(
+    modes: &[Mode],
+    sponge: &mut Sponge,
+    name: &String,
+    progress: bool,
+    range: ByteRange,
+) -> Result<Hashed, Sha3Error> {
+    // progress is only reported from the read loop, so a file being watched is always read
+    #[cfg(feature = "mmap")]
+    if modes.len() == 1
+        && !progress
+        && range == ByteRange::default()
+        && name != "-"
+        && let Ok(metadata) = fs::metadata(name)
+        && metadata.is_file()
+        && metadata.len() >= MMAP_THRESHOLD
+    {
+        let started = Instant::now();
+        sponge.reset();
+        sponge.absorb_mmap(Path::new(name))?;
+
+        return Ok(Hashed {
+            digests: vec![sponge.squeeze_bytes()],
+            bytes: metadata.len(),
+            elapsed: started.elapsed(),
+        });
+    }
+
+    let (reader, size) = open_input(name, sponge.buffer_size(), range)?;
+    let reader = reader.take(range.length.unwrap_or(u64::MAX));
+
+    // the clock starts once the input is open, so only reading and hashing are timed
+    let started = Instant::now();
+    let (digests, bytes) = absorb_input(
+        modes,
+        sponge,
+        reader,
+        ProgressLine::start(progress, name, size),
+    )?;
+
+    Ok(Hashed {
+        digests,
+        bytes,
+        elapsed: started.elapsed(),
+    })
+}
+
+// hashes all of `reader` in each of `modes`, returning the digests and how many bytes were read
+fn absorb_input(
+    modes: &[Mode],
+    sponge: &mut Sponge,
+    reader: impl Read,
+    mut line: Option<ProgressLine>,
+) -> Result<(Vec<Vec<u8>>, u64), Sha3Error> {
+    let mut bytes = 0;
+    let mut absorbed = |done| {
+        bytes = done;
+        if let Some(line) = &mut line {
+            line.draw(done);
+        }
+    };
+
+    let digests = if modes.len() == 1 {
+        sponge.reset();
+        sponge.absorb_reader_with_progress(reader, &mut absorbed)?;
+        vec![sponge.squeeze_bytes()]
+    } else {
+        // every mode is fed from the same reads, so the input is only read once
+        let mut sponges = MultiSponge::new(modes).with_buffer_size(sponge.buffer_size());
+        sponges.absorb_reader_with_progress(reader, &mut absorbed)?;
+        sponges.finalize()
+    };
+
+    Ok((digests, bytes))
+}
+
+// hashes `files` one after another as a single input, with nothing between them, for --concat
+fn digest_concatenated(
+    modes: &[Mode],
+    sponge: &mut Sponge,
+    files: &[String],
+    progress: bool,
+) -> Result<Hashed, Sha3Error> {
+    // the files are chained into one reader, with a single progress line over all of them that
+    // is only drawn when every size is known
+    let mut reader: Box<dyn Read> = Box::new(io::empty());
+    let mut size = Some(0);
+    for name in files {
+        let (next, next_size) = open_input(name, sponge.buffer_size(), ByteRange::default())?;
+        reader = Box::new(reader.chain(next));
+        size = size
+            .zip(next_size)
+            .map(|(size, next_size)| size + next_size);
+    }
+
+    let started = Instant::now();
+    let line = ProgressLine::start(progress, &files.join(" + "), size);
+    let (digests, bytes) = absorb_input(modes, sponge, reader, line)?;
+
+    Ok(Hashed {
+        digests,
+        bytes,
+        elapsed: started.elapsed(),
+    })
+}
+
+type FileDigests = Result<Hashed, Sha3Error>;
+
+// a sponge for `mode` that reads `buffer_size` bytes at a time, or the library default
+fn new_sponge(mode: Mode, buffer_size: Option<usize>) -> Sponge {
+    let sponge = Sponge::new(mode);
+
+    match buffer_size {
+        Some(size) => sponge.with_buffer_size(size),
+        None => sponge,
+    }
+}
+
+// hashes every file, spread over `jobs` threads when there is more than one, and hands each result
+// to `emit` in the order the files were given; progress is only drawn when hashing one at a time
+fn hash_files(
+    modes: &[Mode],
+    files: &[String],
+    jobs: usize,
+    progress: bool,
+    buffer_size: Option<usize>,
+    range: ByteRange,
+    mut emit: impl FnMut(&String, FileDigests),
+) {
+    if jobs <= 1 || files.len() <= 1 {
+        let mut sponge = new_sponge(modes[0], buffer_size);
+
+        for name in files {
+            emit(
+                name,
+                digest_input(modes, &mut sponge, name, progress, range),
+            );
+        }
+
+        return;
+    }
+
+    let next_file = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel::<(usize, FileDigests)>();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(files.len()) {
+            let sender = sender.clone();
+            let next_file = &next_file;
+
+            scope.spawn(move || {
+                let mut sponge = new_sponge(modes[0], buffer_size);
+
+                // each worker claims the next unhashed file until none are left; a failed file is
+                // just another result, so it never stops a worker
+                loop {
+                    let index = next_file.fetch_add(1, Ordering::Relaxed);
+                    let Some(name) = files.get(index) else {
+                        break;
+                    };
+
+                    if sender
+                        .send((index, digest_input(modes, &mut sponge, name, false, range)))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+
+        drop(sender);
+
+        // results arrive in whatever order they finish, so hold the early ones back
+        let mut finished = BTreeMap::new();
+        let mut next_to_emit = 0;
+
+        for (index, result) in receiver {
+            finished.insert(index, result);
+
+            while let Some(result) = finished.remove(&next_to_emit) {
+                emit(&files[next_to_emit], result);
+                next_to_emit += 1;
+            }
+        }
+    });
+}
+
+// where a --checkpoint run had got to: the sponge after the first `offset` bytes of `file`
+#[cfg(feature = "checkpoint")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Checkpoint {
+    file: String,
+    mode: Mode,
+    offset: u64,
+    state: sha3sum::SpongeState,
+}
+
+// how often --checkpoint saves the sponge, unless --checkpoint-interval says otherwise
+#[cfg(feature = "checkpoint")]
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 1 << 30;
 
-    let mut args: Vec<String> = env::args().collect();
-    args.remove(0);
+#[cfg(feature = "checkpoint")]
+fn load_checkpoint(path: &String) -> Result<Checkpoint, Sha3Error> {
+    Ok(serde_json::from_slice(&fs::read(path)?).map_err(io::Error::from)?)
+}
+
+// writes the checkpoint beside `path` and renames it into place once it is on disk, so a crash
+// mid-write leaves the previous checkpoint intact
+#[cfg(feature = "checkpoint")]
+fn save_checkpoint(path: &String, checkpoint: &Checkpoint) -> io::Result<()> {
+    let temporary = format!("{}.tmp", path);
+    let mut file = File::create(&temporary)?;
+    file.write_all(&serde_json::to_vec(checkpoint)?)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+// hashes one file, saving the sponge to `checkpoint` every `interval` bytes and, with `resumed`,
+// carrying on from where that checkpoint left off; the checkpoint is deleted once the file is done
+#[cfg(feature = "checkpoint")]
+fn hash_resumable(
+    mode: Mode,
+    name: &String,
+    buffer_size: Option<usize>,
+    checkpoint: Option<&String>,
+    interval: u64,
+    resumed: Option<Checkpoint>,
+) -> Result<Hashed, Sha3Error> {
+    let describe = |path: &String, error: io::Error| {
+        Sha3Error::Io(io::Error::new(
+            error.kind(),
+            format!("cannot write checkpoint '{}': {}", path, error),
+        ))
+    };
+
+    let (sponge, mut offset) = match resumed {
+        Some(resumed) => (Sponge::from_snapshot(resumed.state)?, resumed.offset),
+        None => (Sponge::new(mode), 0),
+    };
+    let mut sponge = match buffer_size {
+        Some(size) => sponge.with_buffer_size(size),
+        None => sponge,
+    };
+
+    let mut file = File::open(name)?;
+    sha3sum::advise_sequential(&file);
+    if file.metadata()?.len() < offset {
+        return Err(Sha3Error::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file is shorter than the checkpoint, it must have changed since",
+        )));
+    }
+    file.seek(SeekFrom::Start(offset))?;
+
+    let started = Instant::now();
+    let resumed_at = offset;
+    let mut next_checkpoint = offset + interval;
+    let mut buffer = vec![0; sponge.buffer_size()];
+
+    loop {
+        let count = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        sponge.update(&buffer[..count]);
+        offset += count as u64;
+
+        if let Some(path) = checkpoint
+            && offset >= next_checkpoint
+        {
+            let saved = Checkpoint {
+                file: name.clone(),
+                mode,
+                offset,
+                state: sponge.snapshot(),
+            };
+            save_checkpoint(path, &saved).map_err(|error| describe(path, error))?;
+            next_checkpoint = offset + interval;
+        }
+    }
+
+    if let Some(path) = checkpoint {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(describe(path, error));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Hashed {
+        digests: vec![sponge.finalize()],
+        bytes: offset - resumed_at,
+        elapsed: started.elapsed(),
+    })
+}
+
+// "N bytes in S s (R MB/s)" for --timing
+fn describe_timing(bytes: u64, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64();
+    let megabytes_per_second = if seconds > 0.0 {
+        bytes as f64 / 1_000_000.0 / seconds
+    } else {
+        0.0
+    };
+
+    format!(
+        "{} bytes in {:.3} s ({:.1} MB/s)",
+        bytes, seconds, megabytes_per_second
+    )
+}
+
+// how digests are written out
+#[derive(Clone, Copy, Default)]
+enum DigestFormat {
+    #[default]
+    Hex,
+    UpperHex,
+    Base64,
+    Base64Url,
+}
+
+impl DigestFormat {
+    fn encode(self, digest: &[u8]) -> String {
+        match self {
+            DigestFormat::Hex => to_hex(digest),
+            DigestFormat::UpperHex => to_hex_upper(digest),
+            DigestFormat::Base64 => to_base64(digest),
+            DigestFormat::Base64Url => to_base64_url(digest),
+        }
+    }
+}
+
+// expands wildcard arguments the shell left alone (as on Windows); an argument is only treated as
+// a pattern when it contains glob metacharacters and does not name an existing file, so names the
+// shell already expanded are never expanded twice
+#[cfg(feature = "glob")]
+fn expand_globs(files: Vec<String>) -> Vec<String> {
+    let mut expanded = Vec::new();
+
+    for argument in files {
+        let is_pattern = argument.contains(['*', '?', '[']) && !Path::new(&argument).exists();
+        let matches: Vec<String> = match glob::glob(&argument) {
+            Ok(paths) if is_pattern => paths
+                .filter_map(Result::ok)
+                .filter_map(|path| path.to_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        // a pattern that matches nothing is kept, so it is reported as a missing file
+        if matches.is_empty() {
+            expanded.push(argument);
+        } else {
+            expanded.extend(matches);
+        }
+    }
+
+    expanded
+}
+
+// how -r walks a tree, and which directories it is inside of
+struct TreeWalk {
+    // follow symlinks (-L) instead of skipping them
+    dereference: bool,
+    verbose: bool,
+    // canonical paths of the directories being walked, so a symlink back up the tree is caught
+    // instead of recursing forever
+    ancestors: Vec<PathBuf>,
+}
+
+impl TreeWalk {
+    // appends every regular file below `dir` depth-first in name order, skipping special files
+    // and, unless dereferencing, symlinks; problems are reported as they are found and make the
+    // result false
+    fn collect(&mut self, dir: &Path, files: &mut Vec<String>) -> bool {
+        let canonical = match fs::canonicalize(dir) {
+            Ok(canonical) => canonical,
+            Err(error) => return report_path_error(dir, error),
+        };
+
+        if self.ancestors.contains(&canonical) {
+            eprintln!("sha3sum: {}: File system loop detected", dir.display());
+            return false;
+        }
+
+        self.ancestors.push(canonical);
+        let all_ok = self.collect_entries(dir, files);
+        self.ancestors.pop();
+
+        all_ok
+    }
+
+    fn collect_entries(&mut self, dir: &Path, files: &mut Vec<String>) -> bool {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(error) => return report_path_error(dir, error),
+        };
+
+        let mut entries: Vec<_> = match entries.collect::<io::Result<_>>() {
+            Ok(entries) => entries,
+            Err(error) => return report_path_error(dir, error),
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut all_ok = true;
 
-    let args_clone: Vec<String> = args.clone();
+        for entry in entries {
+            let path = entry.path();
 
-    for i in 0..args_clone.len() {
-        if args_clone[i] == "-m" {
-            mode = Mode::try_from(
-                args_clone
-                    .get(i + 1)
-                    .expect("Please provide a mode value (224, 256, 384, 512)"),
-            )
-            .expect("Invalid mode (224, 256, 384, 512)");
+            // `DirEntry::file_type` does not follow symlinks, so they show up as neither kind
+            let mut file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(error) => {
+                    all_ok &= report_path_error(&path, error);
+                    continue;
+                }
+            };
+
+            if file_type.is_symlink() {
+                if !self.dereference {
+                    continue;
+                }
+
+                if self.verbose
+                    && let Ok(target) = fs::read_link(&path)
+                {
+                    eprintln!(
+                        "sha3sum: following {} -> {}",
+                        path.display(),
+                        target.display()
+                    );
+                }
+
+                file_type = match fs::metadata(&path) {
+                    Ok(metadata) => metadata.file_type(),
+                    Err(error) => {
+                        all_ok &= report_path_error(&path, error);
+                        continue;
+                    }
+                };
+            }
+
+            if file_type.is_dir() {
+                all_ok &= self.collect(&path, files);
+            } else if file_type.is_file() {
+                match path.to_str() {
+                    Some(name) => files.push(name.to_string()),
+                    None => {
+                        eprintln!("sha3sum: {}: file name is not valid UTF-8", path.display());
+                        all_ok = false;
+                    }
+                }
+            }
+        }
+
+        all_ok
+    }
+}
+
+// quotes `value` for use inside a JSON string
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            control if control < ' ' => escaped.push_str(&format!("\\u{:04x}", control as u32)),
+            other => escaped.push(other),
+        }
+    }
+
+    escaped
+}
+
+// reads the names of the files to hash, one per line, or NUL-terminated under -z
+fn read_file_list(list_name: &String, zero: bool) -> Result<Vec<String>, Sha3Error> {
+    let reader: Box<dyn BufRead> = if list_name == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(list_name)?))
+    };
+
+    let delimiter = if zero { b'\0' } else { b'\n' };
+    let mut names = Vec::new();
+
+    for name in reader.split(delimiter) {
+        let name = name?;
+
+        if name.is_empty() {
+            continue;
+        }
+
+        match String::from_utf8(name) {
+            Ok(name) => names.push(name),
+            Err(error) => eprintln!(
+                "sha3sum: {}: skipping a file name that is not valid UTF-8: {}",
+                list_name,
+                String::from_utf8_lossy(error.as_bytes())
+            ),
+        }
+    }
+
+    Ok(names)
+}
+
+fn report_path_error(path: &Path, error: io::Error) -> bool {
+    eprintln!(
+        "sha3sum: {}: {}",
+        path.display(),
+        describe_error(&error.into())
+    );
+    false
+}
+
+// one entry of a checksum list, with the mode it names or else the one it is checked with
+struct CheckLine<'a> {
+    mode: Mode,
+    digest: &'a str,
+    name: &'a str,
+}
+
+// a digest is only well formed at the full length of its mode, so a truncated line is
+// improperly formatted rather than a mismatch
+fn is_hex_digest(digest: &str, mode: Mode) -> bool {
+    digest.len() == 2 * mode.output_len() && digest.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+// accepts both the BSD "SHA3-256 (<name>) = <hex>" form and the GNU "<hex> <indicator><name>"
+// form; lines that don't name their algorithm are read as `mode`
+fn parse_check_line(line: &str, mode: Mode) -> Option<CheckLine<'_>> {
+    if let Some((tag, rest)) = line.split_once(" (")
+        && let Some((name, digest)) = rest.rsplit_once(") = ")
+        && let Ok(mode) = tag.parse::<Mode>()
+    {
+        if !is_hex_digest(digest, mode) || name.is_empty() {
+            return None;
+        }
+
+        return Some(CheckLine { mode, digest, name });
+    }
+
+    // several -m at once label each GNU-style line with its mode
+    let (mode, line) = match line
+        .split_once(": ")
+        .and_then(|(label, rest)| Some((label.parse::<Mode>().ok()?, rest)))
+    {
+        Some((labeled, rest)) => (labeled, rest),
+        None => (mode, line),
+    };
+
+    // the indicator is ' ' for text mode and '*' for binary mode, which read files identically
+    let (digest, rest) = line.split_once(' ')?;
+    let name = rest.strip_prefix([' ', '*'])?;
+
+    if !is_hex_digest(digest, mode) || name.is_empty() {
+        return None;
+    }
+
+    Some(CheckLine { mode, digest, name })
+}
+
+// SGR color codes for check mode verdicts
+const GREEN: &str = "32";
+const RED: &str = "31";
+
+// flags that only change how -c reads lists and reports results
+#[derive(Default)]
+struct CheckOptions {
+    zero: bool,
+    quiet: bool,
+    // report through the exit status alone
+    status: bool,
+    // skip listed files that do not exist instead of failing them
+    ignore_missing: bool,
+    // fail the list if any line is improperly formatted
+    strict: bool,
+    // wrap OK and FAILED in ANSI colors
+    color: bool,
+    // --buffer-size, for reading the listed files
+    buffer_size: Option<usize>,
+}
 
-            args.remove(i);
-            args.remove(i);
+impl CheckOptions {
+    fn paint(&self, verdict: &str, color: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", color, verdict)
+        } else {
+            verdict.to_string()
         }
     }
+}
+
+// verifies every line of a checksum list, returning whether all of them matched
+fn check_list(
+    out: &mut impl Write,
+    mode: Mode,
+    list_name: &String,
+    options: &CheckOptions,
+) -> bool {
+    let reader: Box<dyn BufRead> = if list_name == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        match File::open(list_name) {
+            Ok(file_handle) => Box::new(BufReader::new(file_handle)),
+            Err(error) => {
+                eprintln!("sha3sum: {}: {}", list_name, describe_error(&error.into()));
+                return false;
+            }
+        }
+    };
+
+    let mut entries = 0;
+    let mut malformed = 0;
+    let mut verified = 0;
+    let mut mismatched = 0;
+    let mut unreadable = 0;
+
+    // under -z the list holds NUL-terminated entries, so names may contain newlines
+    let delimiter = if options.zero { b'\0' } else { b'\n' };
+
+    for line in reader.split(delimiter) {
+        let mut line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("sha3sum: {}: {}", list_name, describe_error(&error.into()));
+                return false;
+            }
+        };
+
+        if !options.zero && line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        let Ok(line) = String::from_utf8(line) else {
+            malformed += 1;
+            continue;
+        };
+
+        // blank lines and comments are neither entries nor malformed
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some(entry) = parse_check_line(&line, mode) else {
+            malformed += 1;
+            continue;
+        };
+
+        entries += 1;
+        let file_name = entry.name;
+        let mut sponge = new_sponge(entry.mode, options.buffer_size);
+
+        let result = hash_input(&mut sponge, &file_name.to_string());
+
+        if options.ignore_missing
+            && let Err(Sha3Error::Io(error)) = &result
+            && error.kind() == io::ErrorKind::NotFound
+        {
+            continue;
+        }
+
+        if result.is_ok() {
+            verified += 1;
+        }
+
+        match result {
+            Ok(digest) if to_hex(&digest).eq_ignore_ascii_case(entry.digest) => {
+                if !options.quiet && !options.status {
+                    write_out(
+                        out,
+                        format_args!("{}: {}\n", file_name, options.paint("OK", GREEN)),
+                    );
+                }
+            }
+            Ok(_) => {
+                if !options.status {
+                    write_out(
+                        out,
+                        format_args!("{}: {}\n", file_name, options.paint("FAILED", RED)),
+                    );
+                }
+                mismatched += 1;
+            }
+            Err(error) => {
+                if !options.status {
+                    eprintln!("sha3sum: {}: {}", file_name, describe_error(&error));
+                    write_out(
+                        out,
+                        format_args!(
+                            "{}: {}\n",
+                            file_name,
+                            options.paint("FAILED open or read", RED)
+                        ),
+                    );
+                }
+                unreadable += 1;
+            }
+        }
+    }
+
+    // a list without a single usable line is itself an error, not a vacuous success
+    let no_entries = entries == 0;
+    // with every listed file skipped there is nothing to vouch for
+    let nothing_verified = !no_entries && options.ignore_missing && verified == 0;
+    let success = !no_entries
+        && mismatched == 0
+        && unreadable == 0
+        && !nothing_verified
+        && !(options.strict && malformed > 0);
+
+    if options.status {
+        return success;
+    }
+
+    if no_entries {
+        eprintln!(
+            "sha3sum: {}: no properly formatted checksum lines found",
+            list_name
+        );
+        return success;
+    }
+
+    // malformed lines are reported apart from mismatches, they say nothing about any file
+    if malformed > 0 {
+        let (count, verb) = if malformed == 1 {
+            ("line", "is")
+        } else {
+            ("lines", "are")
+        };
+        eprintln!(
+            "sha3sum: WARNING: {} {} {} improperly formatted",
+            malformed, count, verb
+        );
+    }
+
+    if nothing_verified {
+        eprintln!("sha3sum: {}: no file was verified", list_name);
+    }
+
+    if unreadable > 0 {
+        let plural = if unreadable == 1 { "file" } else { "files" };
+        eprintln!(
+            "sha3sum: WARNING: {} listed {} could not be read",
+            unreadable, plural
+        );
+    }
+
+    if mismatched > 0 {
+        let plural = if mismatched == 1 {
+            "checksum"
+        } else {
+            "checksums"
+        };
+        eprintln!(
+            "sha3sum: WARNING: {} computed {} did NOT match",
+            mismatched, plural
+        );
+    }
+
+    success
+}
+
+const USAGE: &str = "\
+Usage: sha3sum [OPTION]... [FILE]...
+Print or check SHA-3 checksums. With no FILE, or when FILE is -, read standard input.
+
+  -m, --mode MODE       224, 256, 384, 512, shake128, shake256, keccak256, keccak512,
+                        cshake128, cshake256, rawshake128 or rawshake256 (default 224);
+                        may be repeated to print one labeled line per mode
+  -b, --binary          mark sum lines with '*' (binary mode)
+  -t, --text            mark sum lines with ' ' (text mode, the default)
+      --tag             print BSD-style 'SHA3-256 (FILE) = HEX' lines
+      --json            print a JSON array of {file, algorithm, hash} objects
+  -u, --uppercase       print digests with uppercase hex digits
+      --base64          print digests in padded Base64 instead of hex
+      --base64-url      print digests in URL-safe Base64 without padding
+  -z, --zero            end each output line with NUL instead of newline
+      --files-from FILE read the names of the files to hash from FILE (- for standard
+                        input), one per line or NUL-terminated with -z
+      --filenames0      read NUL-separated file names from standard input, as printed by
+                        find -print0
+  -j, --jobs N          hash N files at a time (0 for one per CPU); output keeps the
+                        order the files were given in
+      --buffer-size SIZE
+                        read SIZE bytes of each file at a time, optionally with a K, M
+                        or G suffix (default 64K, rounded up to whole blocks)
+      --progress        show how far through each file hashing is, on stderr, when stdout
+                        is a terminal and the file size is known
+      --skip SIZE       ignore the first SIZE bytes of each FILE, optionally with a K, M
+                        or G suffix, seeking past them where the FILE allows it
+      --length SIZE     hash only the first SIZE bytes of each FILE (after any skipped
+                        ones), with the same suffixes; shorter FILEs are hashed whole
+      --concat          hash the FILEs one after another as a single input, printing
+                        one digest for the whole sequence; the order matters
+      --timing          print how long hashing each file took and its throughput to
+                        stderr, followed by a total
+  -r, --recursive       hash every regular file below each directory given
+  -L, --dereference     follow symlinks found while recursing instead of skipping them
+  -v, --verbose         report each symlink followed
+      --checkpoint FILE save how far hashing has got to FILE every so often, so an
+                        interrupted run can be picked up with --resume; FILE is deleted
+                        once hashing finishes (one file and one mode only, and only when
+                        built with the checkpoint feature)
+      --checkpoint-interval SIZE
+                        save a checkpoint every SIZE bytes hashed (default 1G)
+      --resume FILE     carry on from the checkpoint in FILE; the file and mode it names
+                        are used unless given, and must match if they are; saving
+                        carries on to FILE unless --checkpoint names another
+  -c, --check           read checksums from the FILEs and verify them
+
+The following options only apply when verifying checksums:
+      --ignore-missing  don't fail or report status for missing files
+      --quiet           don't print OK for each successfully verified file
+      --status          don't output anything, the exit status shows success
+      --strict          exit non-zero for improperly formatted checksum lines
+      --color[=WHEN]    color OK and FAILED: always (the default with no WHEN), never,
+                        or auto, which colors only a terminal and honors NO_COLOR
+
+      --list            list the supported modes and exit
+      --help            display this help and exit
+      --version         output version information and exit
+";
+
+// everything the command line asked for
+#[derive(Default)]
+struct Options {
+    // every distinct -m, in the order given
+    modes: Vec<Mode>,
+    check: bool,
+    tag: bool,
+    json: bool,
+    format: DigestFormat,
+    binary: bool,
+    zero: bool,
+    recursive: bool,
+    dereference: bool,
+    verbose: bool,
+    check_options: CheckOptions,
+    files: Vec<String>,
+    files_from: Option<String>,
+    // --filenames0: the list is standard input, NUL-separated whatever -z says
+    filenames0: bool,
+    // worker threads for hashing, 0 meaning one per CPU
+    jobs: usize,
+    // bytes read from each file at a time, unset for the library default
+    buffer_size: Option<usize>,
+    progress: bool,
+    timing: bool,
+    // --concat: hash all the files as one input
+    concat: bool,
+    // --skip and --length
+    range: ByteRange,
+    color: ColorChoice,
+    // --checkpoint, --checkpoint-interval and --resume
+    #[cfg(feature = "checkpoint")]
+    checkpoint: Option<String>,
+    #[cfg(feature = "checkpoint")]
+    checkpoint_interval: Option<u64>,
+    #[cfg(feature = "checkpoint")]
+    resume: Option<String>,
+}
+
+#[derive(Clone, Copy, Default)]
+enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    // auto colors only a terminal, and only if NO_COLOR is unset or empty (see no-color.org)
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            }
+        }
+    }
+}
+
+impl Options {
+    fn takes_value(name: &str) -> bool {
+        matches!(
+            name,
+            "mode" | "jobs" | "buffer-size" | "files-from" | "skip" | "length"
+        ) || cfg!(feature = "checkpoint")
+            && matches!(name, "checkpoint" | "checkpoint-interval" | "resume")
+    }
+
+    // applies an option that takes a value, by its long name
+    fn set_value(&mut self, name: &str, value: &str) -> Result<(), String> {
+        match name {
+            "mode" => {
+                let mode = value
+                    .parse::<Mode>()
+                    .map_err(|error| describe_error(&error))?;
+
+                if !self.modes.contains(&mode) {
+                    self.modes.push(mode);
+                }
+            }
+            "jobs" => {
+                self.jobs = value
+                    .parse()
+                    .map_err(|_| format!("invalid number of jobs: '{}'", value))?;
+            }
+            "buffer-size" => {
+                self.buffer_size = Some(
+                    parse_size(value).ok_or_else(|| format!("invalid buffer size: '{}'", value))?,
+                );
+            }
+            "files-from" => self.files_from = Some(value.to_string()),
+            "skip" => {
+                self.range.skip = parse_byte_count(value)
+                    .ok_or_else(|| format!("invalid number of bytes to skip: '{}'", value))?;
+            }
+            "length" => {
+                self.range.length = Some(
+                    parse_byte_count(value)
+                        .ok_or_else(|| format!("invalid length: '{}'", value))?,
+                );
+            }
+            #[cfg(feature = "checkpoint")]
+            "checkpoint" => self.checkpoint = Some(value.to_string()),
+            #[cfg(feature = "checkpoint")]
+            "checkpoint-interval" => {
+                self.checkpoint_interval = Some(
+                    parse_size(value)
+                        .ok_or_else(|| format!("invalid checkpoint interval: '{}'", value))?
+                        as u64,
+                );
+            }
+            #[cfg(feature = "checkpoint")]
+            "resume" => self.resume = Some(value.to_string()),
+            _ => unreachable!("{} takes no value", name),
+        }
+
+        Ok(())
+    }
+
+    // applies a flag that takes no value, by its long name
+    fn set_flag(&mut self, name: &str) -> bool {
+        match name {
+            "binary" => self.binary = true,
+            "text" => self.binary = false,
+            "check" => self.check = true,
+            "tag" => self.tag = true,
+            "json" => self.json = true,
+            "uppercase" => self.format = DigestFormat::UpperHex,
+            "base64" => self.format = DigestFormat::Base64,
+            "base64-url" => self.format = DigestFormat::Base64Url,
+            "zero" => self.zero = true,
+            "recursive" => self.recursive = true,
+            "dereference" => self.dereference = true,
+            "verbose" => self.verbose = true,
+            "progress" => self.progress = true,
+            "filenames0" => {
+                self.files_from = Some(String::from("-"));
+                self.filenames0 = true;
+            }
+            "timing" => self.timing = true,
+            "concat" => self.concat = true,
+            "quiet" => self.check_options.quiet = true,
+            "status" => self.check_options.status = true,
+            "ignore-missing" => self.check_options.ignore_missing = true,
+            "strict" => self.check_options.strict = true,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+// a byte count with an optional K, M or G suffix (powers of 1024), e.g. "256K"
+fn parse_byte_count(value: &str) -> Option<u64> {
+    let (digits, multiplier) = match value.char_indices().last()? {
+        (index, 'K' | 'k') => (&value[..index], 1 << 10),
+        (index, 'M' | 'm') => (&value[..index], 1 << 20),
+        (index, 'G' | 'g') => (&value[..index], 1 << 30),
+        _ => (value, 1),
+    };
+
+    digits.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+// a byte count, as above, that is more than zero and fits in memory
+fn parse_size(value: &str) -> Option<usize> {
+    parse_byte_count(value)
+        .filter(|&size| size > 0)
+        .and_then(|size| usize::try_from(size).ok())
+}
+
+enum Command {
+    Help,
+    Version,
+    List,
+    Run(Box<Options>),
+}
+
+// parses coreutils-style arguments: clustered short flags, `--name` and `--name=value` long
+// options, and `--` to treat everything after it as a file
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut options = Options {
+        jobs: 1,
+        ..Options::default()
+    };
+
+    while let Some(argument) = args.next() {
+        if argument == "--" {
+            options.files.extend(args.by_ref());
+            break;
+        }
+
+        if let Some(long) = argument.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (long, None),
+            };
+
+            match name {
+                "help" => return Ok(Command::Help),
+                "version" => return Ok(Command::Version),
+                "list" => return Ok(Command::List),
+                // a bare --color means always, so its value can only come after '='
+                "color" => {
+                    options.color = match value.as_deref() {
+                        None | Some("always" | "yes" | "force") => ColorChoice::Always,
+                        Some("auto" | "tty" | "if-tty") => ColorChoice::Auto,
+                        Some("never" | "no" | "none") => ColorChoice::Never,
+                        Some(other) => {
+                            return Err(format!("invalid argument '{}' for '--color'", other));
+                        }
+                    };
+                }
+                _ if Options::takes_value(name) => {
+                    let value = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("option '--{}' requires an argument", name))?;
+                    options.set_value(name, &value)?;
+                }
+                _ if value.is_some() && options.set_flag(name) => {
+                    return Err(format!("option '--{}' doesn't allow an argument", name));
+                }
+                _ if options.set_flag(name) => {}
+                _ => return Err(format!("unrecognized option '{}'", argument)),
+            }
+
+            continue;
+        }
+
+        // a lone "-" is standard input, not a flag
+        let Some(cluster) = argument
+            .strip_prefix('-')
+            .filter(|cluster| !cluster.is_empty())
+        else {
+            options.files.push(argument);
+            continue;
+        };
+
+        for (index, flag) in cluster.char_indices() {
+            let name = match flag {
+                'm' => "mode",
+                'j' => "jobs",
+                'b' => "binary",
+                't' => "text",
+                'c' => "check",
+                'z' => "zero",
+                'u' => "uppercase",
+                'r' => "recursive",
+                'L' => "dereference",
+                'v' => "verbose",
+                _ => return Err(format!("invalid option -- '{}'", flag)),
+            };
+
+            // the rest of the cluster, or else the next argument, is the value
+            if Options::takes_value(name) {
+                let rest = &cluster[index + flag.len_utf8()..];
+                let value = if rest.is_empty() {
+                    args.next()
+                        .ok_or_else(|| format!("option requires an argument -- '{}'", flag))?
+                } else {
+                    rest.to_string()
+                };
+                options.set_value(name, &value)?;
+                break;
+            }
+
+            options.set_flag(name);
+        }
+    }
+
+    Ok(Command::Run(Box::new(options)))
+}
+
+fn main() -> ExitCode {
+    // locked once for the whole run; only this thread ever writes to it
+    let mut out = io::stdout().lock();
+
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            write_out(&mut out, format_args!("{}", USAGE));
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            write_out(
+                &mut out,
+                format_args!("sha3sum {}\n", env!("CARGO_PKG_VERSION")),
+            );
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::List) => {
+            for mode in all_modes() {
+                write_out(&mut out, format_args!("{}\n", mode));
+            }
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("sha3sum: {}", message);
+            eprintln!("Try 'sha3sum --help' for more information.");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Options {
+        mut modes,
+        check,
+        tag,
+        json,
+        format,
+        binary,
+        zero,
+        recursive,
+        dereference,
+        verbose,
+        mut check_options,
+        mut files,
+        files_from,
+        filenames0,
+        jobs,
+        buffer_size,
+        progress,
+        timing,
+        concat,
+        range,
+        color,
+        #[cfg(feature = "checkpoint")]
+        checkpoint,
+        #[cfg(feature = "checkpoint")]
+        checkpoint_interval,
+        #[cfg(feature = "checkpoint")]
+        resume,
+    } = *options;
+
+    #[cfg(feature = "glob")]
+    {
+        files = expand_globs(files);
+    }
+
+    // listed names are taken verbatim, only command-line arguments are patterns
+    if let Some(list_name) = &files_from {
+        match read_file_list(list_name, zero || filenames0) {
+            Ok(names) => files.extend(names),
+            Err(error) => {
+                eprintln!("sha3sum: {}: {}", list_name, describe_error(&error));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // a checkpoint names the file and mode it was saved for, so neither has to be repeated
+    #[cfg(feature = "checkpoint")]
+    let resumed = match &resume {
+        Some(path) => match load_checkpoint(path) {
+            Ok(resumed) => {
+                if files.is_empty() && files_from.is_none() {
+                    files.push(resumed.file.clone());
+                }
+                if modes.is_empty() {
+                    modes.push(resumed.mode);
+                }
+                Some(resumed)
+            }
+            Err(error) => {
+                eprintln!("sha3sum: {}: {}", path, describe_error(&error));
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    // a resumed run keeps saving to the checkpoint it came from, unless told to save elsewhere
+    #[cfg(feature = "checkpoint")]
+    let checkpoint = checkpoint.or_else(|| resume.clone());
+
+    // with no files given, hash standard input instead (an empty --files-from list names none)
+    if files.is_empty() && files_from.is_none() {
+        files.push(String::from("-"));
+    }
+
+    if modes.is_empty() {
+        modes.push(Mode::default());
+    }
+
+    // GNU-style lines do not name their algorithm, so they are checked with the first mode
+    let mode = modes[0];
+
+    // a checkpoint holds one sponge part way through one file
+    #[cfg(feature = "checkpoint")]
+    if checkpoint.is_some() || resumed.is_some() {
+        let problem = if check || modes.len() != 1 || files.len() != 1 || files[0] == "-" {
+            Some(String::from(
+                "--checkpoint and --resume need exactly one file and one mode",
+            ))
+        } else if range != ByteRange::default() {
+            // the saved offset is how far into the whole file hashing had got
+            Some(String::from(
+                "--skip and --length cannot be used with --checkpoint or --resume",
+            ))
+        } else if let Some(resumed) = &resumed
+            && (resumed.file != files[0] || resumed.mode != modes[0])
+        {
+            Some(format!(
+                "the checkpoint is for {} of '{}'",
+                resumed.mode, resumed.file
+            ))
+        } else {
+            None
+        };
+
+        if let Some(problem) = problem {
+            eprintln!("sha3sum: {}", problem);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    if check && concat {
+        eprintln!("sha3sum: --concat cannot be used with --check");
+        return ExitCode::FAILURE;
+    }
+
+    if range != ByteRange::default() && (check || concat) {
+        eprintln!("sha3sum: --skip and --length cannot be used with --check or --concat");
+        return ExitCode::FAILURE;
+    }
+
+    if check {
+        check_options.zero = zero;
+        check_options.color = color.enabled();
+        check_options.buffer_size = buffer_size;
+        let mut all_ok = true;
+
+        for list_name in &files {
+            all_ok &= check_list(&mut out, mode, list_name, &check_options);
+        }
+
+        return if all_ok {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+
+    let indicator = if binary { '*' } else { ' ' };
+    let terminator = if zero { '\0' } else { '\n' };
+
+    // a file that cannot be hashed is reported and skipped, but still fails the run
+    let mut exit_code = ExitCode::SUCCESS;
+
+    // directories named on the command line are replaced by the files inside them
+    if recursive {
+        let mut walk = TreeWalk {
+            dereference,
+            verbose,
+            ancestors: Vec::new(),
+        };
+        let mut expanded = Vec::new();
+
+        for argument in files {
+            if argument != "-" && Path::new(&argument).is_dir() {
+                if !walk.collect(Path::new(&argument), &mut expanded) {
+                    exit_code = ExitCode::FAILURE;
+                }
+            } else {
+                expanded.push(argument);
+            }
+        }
+
+        files = expanded;
+    }
+
+    let mut json_entries = 0;
+
+    if json {
+        write_out(&mut out, format_args!("["));
+    }
+
+    let jobs = match jobs {
+        0 => thread::available_parallelism().map_or(1, usize::from),
+        jobs => jobs,
+    };
+
+    // progress lines would only get in the way of output meant for another program, and several
+    // jobs at once would draw over each other
+    let progress = progress && jobs == 1 && io::stdout().is_terminal();
+
+    let mut total_bytes = 0;
+    let mut total_elapsed = Duration::ZERO;
+
+    let mut emit = |argument: &String, hashed: FileDigests| {
+        let hashed = match hashed {
+            Ok(hashed) => hashed,
+            Err(error) => {
+                eprintln!("sha3sum: {}: {}", argument, describe_error(&error));
+                exit_code = ExitCode::FAILURE;
+                return;
+            }
+        };
+
+        if timing {
+            eprintln!(
+                "sha3sum: {}: {}",
+                argument,
+                describe_timing(hashed.bytes, hashed.elapsed)
+            );
+            total_bytes += hashed.bytes;
+            total_elapsed += hashed.elapsed;
+        }
+
+        for (mode, digest) in modes.iter().zip(hashed.digests) {
+            let digest = format.encode(&digest);
+
+            if json {
+                let separator = if json_entries == 0 { "\n" } else { ",\n" };
+                write_out(
+                    &mut out,
+                    format_args!(
+                        "{}  {{\"file\": \"{}\", \"algorithm\": \"{}\", \"hash\": \"{}\"}}",
+                        separator,
+                        json_escape(argument),
+                        mode,
+                        digest
+                    ),
+                );
+                json_entries += 1;
+            } else if tag {
+                let tag_name = mode.to_string().to_uppercase();
+                write_out(
+                    &mut out,
+                    format_args!("{} ({}) = {}{}", tag_name, argument, digest, terminator),
+                );
+            } else if modes.len() > 1 {
+                // with several modes, every line is labeled with the algorithm that produced it
+                write_out(
+                    &mut out,
+                    format_args!(
+                        "{}: {} {}{}{}",
+                        mode, digest, indicator, argument, terminator
+                    ),
+                );
+            } else {
+                write_out(
+                    &mut out,
+                    format_args!("{} {}{}{}", digest, indicator, argument, terminator),
+                );
+            }
+        }
+    };
+
+    #[cfg(feature = "checkpoint")]
+    if checkpoint.is_some() || resumed.is_some() {
+        emit(
+            &files[0],
+            hash_resumable(
+                modes[0],
+                &files[0],
+                buffer_size,
+                checkpoint.as_ref(),
+                checkpoint_interval.unwrap_or(DEFAULT_CHECKPOINT_INTERVAL),
+                resumed,
+            ),
+        );
+        // hashed already, so there is nothing left for hash_files
+        files.clear();
+    }
+
+    // the files make up one input, so the single line of output names all of them
+    if concat && !files.is_empty() {
+        let mut sponge = new_sponge(modes[0], buffer_size);
+        emit(
+            &files.join(" + "),
+            digest_concatenated(&modes, &mut sponge, &files, progress),
+        );
+        files.clear();
+    }
+
+    hash_files(
+        &modes,
+        &files,
+        jobs,
+        progress,
+        buffer_size,
+        range,
+        &mut emit,
+    );
+
+    if json {
+        write_out(
+            &mut out,
+            format_args!("{}]\n", if json_entries == 0 { "" } else { "\n" }),
+        );
+    }
+
+    if timing {
+        eprintln!(
+            "sha3sum: total: {}",
+            describe_timing(total_bytes, total_elapsed)
+        );
+    }
+
+    exit_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        match parse_args(args.iter().map(|arg| arg.to_string()))? {
+            Command::Run(options) => Ok(*options),
+            _ => Err(String::from("not a run")),
+        }
+    }
+
+    #[test]
+    fn short_flags_cluster() {
+        let options = parse(&["-bm256", "file"]).unwrap();
+        assert!(options.binary);
+        assert_eq!(options.modes, [Mode::Sha3_256]);
+        assert_eq!(options.files, ["file"]);
+
+        // a value-taking flag swallows the rest of its cluster, or else the next argument
+        let options = parse(&["-rLm", "512", "-j4", "-cz"]).unwrap();
+        assert!(options.recursive && options.dereference && options.check && options.zero);
+        assert_eq!(options.modes, [Mode::Sha3_512]);
+        assert_eq!(options.jobs, 4);
+
+        let options = parse(&["-bt", "-u"]).unwrap();
+        assert!(!options.binary);
+        assert!(matches!(options.format, DigestFormat::UpperHex));
+    }
+
+    #[test]
+    fn long_options_take_values_either_way() {
+        let options = parse(&["--mode=384", "--mode", "keccak256", "--length", "1K"]).unwrap();
+        assert_eq!(options.modes, [Mode::Sha3_384, Mode::Keccak256]);
+        assert_eq!(options.range.length, Some(1024));
+
+        let options = parse(&["--skip=0", "--buffer-size", "4M", "--jobs=0"]).unwrap();
+        assert!(options.range == ByteRange::default());
+        assert_eq!(options.buffer_size, Some(4 << 20));
+        assert_eq!(options.jobs, 0);
+
+        // repeats of a mode are dropped, keeping the first position
+        let options = parse(&["-m", "256", "-m", "512", "-m", "256"]).unwrap();
+        assert_eq!(options.modes, [Mode::Sha3_256, Mode::Sha3_512]);
+    }
+
+    #[test]
+    fn double_dash_ends_the_options() {
+        let options = parse(&["-m", "256", "--", "-m", "--tag", "-"]).unwrap();
+        assert_eq!(options.modes, [Mode::Sha3_256]);
+        assert!(!options.tag);
+        assert_eq!(options.files, ["-m", "--tag", "-"]);
+
+        // a lone dash is standard input, not an empty cluster
+        assert_eq!(parse(&["-"]).unwrap().files, ["-"]);
+    }
+
+    #[test]
+    fn commands_stop_parsing() {
+        let parsed = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+
+        assert!(matches!(
+            parsed(&["--help", "--nonsense"]),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parsed(&["--version"]), Ok(Command::Version)));
+        assert!(matches!(parsed(&["-b", "--list"]), Ok(Command::List)));
+    }
+
+    #[test]
+    fn bad_values_are_errors() {
+        for args in [
+            &["-m", "999"][..],
+            &["-m"],
+            &["-bm"],
+            &["--mode"],
+            &["-j", "many"],
+            &["--jobs=-1"],
+            &["--buffer-size", "0"],
+            &["--buffer-size", "12Q"],
+            &["--skip", "K"],
+            &["--length", "99999999999999999999"],
+            &["--color=sometimes"],
+            &["--binary=yes"],
+            &["--nonsense"],
+            &["-q"],
+        ] {
+            assert!(parse(args).is_err(), "{:?}", args);
+        }
+
+        assert_eq!(parse(&["-x"]).err().unwrap(), "invalid option -- 'x'");
+        assert_eq!(
+            parse(&["--tag=1"]).err().unwrap(),
+            "option '--tag' doesn't allow an argument"
+        );
+        assert_eq!(
+            parse(&["--jobs"]).err().unwrap(),
+            "option '--jobs' requires an argument"
+        );
+    }
+
+    #[test]
+    fn check_lines_need_a_full_length_digest() {
+        let sha3_256 = "a".repeat(64);
+        let sha3_512 = "B".repeat(128);
+
+        let line = format!("{}  name", sha3_256);
+        let entry = parse_check_line(&line, Mode::Sha3_256).unwrap();
+        assert_eq!((entry.mode, entry.name), (Mode::Sha3_256, "name"));
+        assert!(parse_check_line(&format!("{}  name", sha3_256), Mode::Sha3_512).is_none());
+        assert!(parse_check_line(&format!("{} *name", &sha3_256[1..]), Mode::Sha3_256).is_none());
+
+        // a tag or label decides the length, whatever mode the list is checked with
+        let tagged = format!("SHA3-512 (name) = {}", sha3_512);
+        assert_eq!(
+            parse_check_line(&tagged, Mode::Sha3_224).unwrap().mode,
+            Mode::Sha3_512
+        );
+        let labeled = format!("sha3-512: {}  name", sha3_512);
+        assert_eq!(
+            parse_check_line(&labeled, Mode::Sha3_224).unwrap().mode,
+            Mode::Sha3_512
+        );
+        assert!(
+            parse_check_line(&format!("SHA3-512 (name) = {}", sha3_256), Mode::Sha3_256).is_none()
+        );
+        assert!(parse_check_line(&format!("{}  name", "g".repeat(64)), Mode::Sha3_256).is_none());
+    }
+
+    #[test]
+    fn byte_counts_take_suffixes() {
+        assert_eq!(parse_byte_count("0"), Some(0));
+        assert_eq!(parse_byte_count("3k"), Some(3 << 10));
+        assert_eq!(parse_byte_count("2G"), Some(2 << 30));
+        assert_eq!(parse_byte_count(""), None);
+        assert_eq!(parse_byte_count("1.5M"), None);
+        assert_eq!(parse_byte_count("17179869184G"), None);
+        assert_eq!(parse_size("0"), None);
+        assert_eq!(parse_size("64K"), Some(64 << 10));
+        assert_eq!(parse_size("2G"), Some(2 << 30));
+        assert_eq!(parse_size("1.5M"), None);
+    }
+
+    #[cfg(feature = "checkpoint")]
+    #[test]
+    fn checkpoint_options_take_values() {
+        let options = parse(&["--checkpoint=saved", "--checkpoint-interval", "16K"]).unwrap();
+        assert_eq!(options.checkpoint.as_deref(), Some("saved"));
+        assert_eq!(options.checkpoint_interval, Some(16 << 10));
+        assert!(parse(&["--checkpoint-interval", "0"]).is_err());
+        assert!(parse(&["--resume"]).is_err());
+    }
 
-    for argument in args {
-        let mut sponge: Sponge = Sponge::new(mode);
+    #[test]
+    fn ranges_cover_what_is_left_after_the_skip() {
+        let range = |skip, length| ByteRange { skip, length };
 
-        sponge.absorb(&argument);
-        println!("{}  {}", sponge.squeeze(), argument);
+        assert_eq!(ByteRange::default().covered(1000), 1000);
+        assert_eq!(range(100, None).covered(1000), 900);
+        assert_eq!(range(100, Some(300)).covered(1000), 300);
+        assert_eq!(range(900, Some(300)).covered(1000), 100);
+        assert_eq!(range(1500, Some(10)).covered(1000), 0);
     }
 }
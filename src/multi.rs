@@ -0,0 +1,88 @@
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::Sha3Error;
+use crate::{DEFAULT_READ_BUFFER_SIZE, Mode, Sponge};
+
+/// One sponge per mode, all fed the same input, so a message can be hashed in several modes
+/// while reading it only once.
+pub struct MultiSponge {
+    sponges: Vec<Sponge>,
+    // bytes `absorb_reader` reads at a time
+    read_buffer_size: usize,
+}
+
+impl MultiSponge {
+    pub fn new(modes: &[Mode]) -> Self {
+        MultiSponge {
+            sponges: modes.iter().map(|&mode| Sponge::new(mode)).collect(),
+            read_buffer_size: DEFAULT_READ_BUFFER_SIZE,
+        }
+    }
+
+    /// Sets how many bytes [`MultiSponge::absorb_reader`] reads at a time, 64 KiB by default.
+    /// The modes have different rates, so unlike [`Sponge::with_buffer_size`] the size is
+    /// used as given; a sponge buffers whatever partial block a read leaves it.
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.read_buffer_size = size.max(1);
+        self
+    }
+
+    /// The read size set by [`MultiSponge::with_buffer_size`].
+    pub fn buffer_size(&self) -> usize {
+        self.read_buffer_size
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for sponge in &mut self.sponges {
+            sponge.update(data);
+        }
+    }
+
+    /// Reads `reader` to the end, handing every chunk to each sponge in turn before reading
+    /// the next.
+    #[cfg(feature = "std")]
+    pub fn absorb_reader<R: Read>(&mut self, reader: R) -> Result<(), Sha3Error> {
+        self.absorb_reader_with_progress(reader, |_| {})
+    }
+
+    /// Like [`MultiSponge::absorb_reader`], but calls `progress` after every chunk with the
+    /// total number of bytes absorbed so far, as [`Sponge::absorb_reader_with_progress`] does.
+    #[cfg(feature = "std")]
+    pub fn absorb_reader_with_progress<R: Read>(
+        &mut self,
+        mut reader: R,
+        mut progress: impl FnMut(u64),
+    ) -> Result<(), Sha3Error> {
+        let mut buffer = alloc::vec![0; self.read_buffer_size];
+        let mut absorbed = 0;
+
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(count) => {
+                    self.update(&buffer[..count]);
+                    absorbed += count as u64;
+                    progress(absorbed);
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    /// Clears every sponge, as [`Sponge::reset`] does.
+    pub fn reset(&mut self) {
+        for sponge in &mut self.sponges {
+            sponge.reset();
+        }
+    }
+
+    /// The digests, in the order the modes were given to [`MultiSponge::new`].
+    pub fn finalize(self) -> Vec<Vec<u8>> {
+        self.sponges.into_iter().map(Sponge::finalize).collect()
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use alloc::{format, vec::Vec};
+
+use crate::{Sha3Error, Sponge};
+
+/// Everything needed to carry on hashing where a [`Sponge`] left off: the lanes, the
+/// parameters that define its mode, the bytes of the block not absorbed yet, and whether the
+/// final block has been padded. Taken with [`Sponge::snapshot`] and turned back into a
+/// sponge with [`Sponge::from_snapshot`].
+///
+/// With the `serde` feature it implements `Serialize` and `Deserialize`, so a long job can
+/// write it to disk and resume after a restart. Anyone able to edit the saved state
+/// controls the digest; against data that may have been tampered with, keep it
+/// authenticated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpongeState {
+    lanes: [u64; 25],
+    rate: usize,
+    output_len: usize,
+    xof: bool,
+    suffix: u8,
+    rounds: usize,
+    initial_lanes: [u64; 25],
+    pending: Vec<u8>,
+    finalized: bool,
+    buffer_size: usize,
+}
+
+impl Sponge {
+    /// Captures the sponge mid-stream, including input [`Sponge::update`] has buffered
+    /// but not absorbed yet.
+    pub fn snapshot(&self) -> SpongeState {
+        SpongeState {
+            lanes: self.state,
+            rate: self.bit_rate,
+            output_len: self.output_len,
+            xof: self.xof,
+            suffix: self.suffix,
+            rounds: self.rounds,
+            initial_lanes: self.initial_state,
+            pending: self.buffer[..self.buffer_len].to_vec(),
+            finalized: self.finalized,
+            buffer_size: self.read_buffer_size,
+        }
+    }
+
+    /// Rebuilds a sponge from a [`SpongeState`]. Feeding it the rest of the input gives the
+    /// same digest as the original sponge would have.
+    ///
+    /// A state that came from outside the program may be malformed, so the parameters are
+    /// checked the way [`Sponge::with_params`] checks them.
+    pub fn from_snapshot(snapshot: SpongeState) -> Result<Self, Sha3Error> {
+        let mut sponge = Sponge::with_params(
+            snapshot.rate,
+            snapshot.output_len,
+            snapshot.suffix,
+            snapshot.xof,
+        )?;
+
+        if !(1..=24).contains(&snapshot.rounds) {
+            return Err(Sha3Error::InvalidParameters(format!(
+                "rounds must be in 1..=24, got {}",
+                snapshot.rounds
+            )));
+        }
+
+        if snapshot.pending.len() >= snapshot.rate {
+            return Err(Sha3Error::InvalidParameters(format!(
+                "pending input must be shorter than the {}-byte rate, got {} bytes",
+                snapshot.rate,
+                snapshot.pending.len()
+            )));
+        }
+
+        sponge.state = snapshot.lanes;
+        sponge.rounds = snapshot.rounds;
+        sponge.initial_state = snapshot.initial_lanes;
+        sponge.buffer[..snapshot.pending.len()].copy_from_slice(&snapshot.pending);
+        sponge.buffer_len = snapshot.pending.len();
+        sponge.finalized = snapshot.finalized;
+
+        Ok(sponge.with_buffer_size(snapshot.buffer_size))
+    }
+}
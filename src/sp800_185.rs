@@ -0,0 +1,215 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{Mode, Sponge};
+
+// encodings from NIST SP 800-185, section 2.3
+
+// big-endian bytes of `value` with leading zeros dropped (but at least one byte)
+fn minimal_be_bytes(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first = bytes.iter().position(|&byte| byte != 0).unwrap_or(7);
+    bytes[first..].to_vec()
+}
+
+pub(crate) fn left_encode(value: u64) -> Vec<u8> {
+    let bytes = minimal_be_bytes(value);
+    let mut encoded = vec![bytes.len() as u8];
+    encoded.extend(bytes);
+    encoded
+}
+
+pub(crate) fn right_encode(value: u64) -> Vec<u8> {
+    let mut encoded = minimal_be_bytes(value);
+    encoded.push(encoded.len() as u8);
+    encoded
+}
+
+pub(crate) fn encode_string(string: &[u8]) -> Vec<u8> {
+    let mut encoded = left_encode(string.len() as u64 * 8);
+    encoded.extend_from_slice(string);
+    encoded
+}
+
+pub(crate) fn bytepad(data: &[u8], width: usize) -> Vec<u8> {
+    let mut padded = left_encode(width as u64);
+    padded.extend_from_slice(data);
+
+    let padded_len = padded.len().div_ceil(width) * width;
+    padded.resize(padded_len, 0);
+    padded
+}
+
+fn kmac(mode: Mode, key: &[u8], data: &[u8], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    let mut sponge = Sponge::new_cshake(mode, b"KMAC", custom).unwrap();
+    let bit_rate = sponge.bit_rate;
+
+    sponge.update(&bytepad(&encode_string(key), bit_rate));
+    sponge.update(data);
+    sponge.update(&right_encode(out_len as u64 * 8));
+    sponge.absorb_padding();
+
+    sponge.squeeze_xof(out_len)
+}
+
+/// KMAC128 from NIST SP 800-185: a MAC of `data` under `key`, `out_len` bytes long, with an
+/// optional customization string.
+pub fn kmac128(key: &[u8], data: &[u8], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    kmac(Mode::CShake128, key, data, out_len, custom)
+}
+
+/// KMAC256 from NIST SP 800-185, see [`kmac128`].
+pub fn kmac256(key: &[u8], data: &[u8], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    kmac(Mode::CShake256, key, data, out_len, custom)
+}
+
+fn tuplehash(mode: Mode, tuples: &[&[u8]], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    let mut sponge = Sponge::new_cshake(mode, b"TupleHash", custom).unwrap();
+
+    for tuple in tuples {
+        sponge.update(&encode_string(tuple));
+    }
+    sponge.update(&right_encode(out_len as u64 * 8));
+    sponge.absorb_padding();
+
+    sponge.squeeze_xof(out_len)
+}
+
+/// TupleHash128 from NIST SP 800-185. Every element is length-prefixed, so `["ab", "c"]`
+/// and `["a", "bc"]` hash differently.
+pub fn tuplehash128(tuples: &[&[u8]], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    tuplehash(Mode::CShake128, tuples, out_len, custom)
+}
+
+/// TupleHash256 from NIST SP 800-185, see [`tuplehash128`].
+pub fn tuplehash256(tuples: &[&[u8]], out_len: usize, custom: &[u8]) -> Vec<u8> {
+    tuplehash(Mode::CShake256, tuples, out_len, custom)
+}
+
+// digests every `block_size` chunk with cSHAKE (empty N and S, so plain SHAKE), spreading
+// contiguous runs of chunks across `threads` threads and keeping the original order; without
+// `std` there are no threads, and every chunk is digested in turn
+fn block_digests(mode: Mode, data: &[u8], block_size: usize, threads: usize) -> Vec<u8> {
+    let (xof_mode, digest_len) = match mode {
+        Mode::CShake128 => (Mode::Shake128, 256 / 8),
+        _ => (Mode::Shake256, 512 / 8),
+    };
+
+    let digest_run = |blocks: &[u8]| -> Vec<u8> {
+        let mut digests = Vec::new();
+        for block in blocks.chunks(block_size) {
+            let mut sponge = Sponge::new(xof_mode);
+            sponge.absorb_bytes(block);
+            digests.extend(sponge.squeeze_xof(digest_len));
+        }
+        digests
+    };
+
+    let block_count = data.len().div_ceil(block_size);
+    if threads <= 1 || block_count <= 1 {
+        return digest_run(data);
+    }
+
+    #[cfg(feature = "std")]
+    {
+        let blocks_per_thread = block_count.div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = data
+                .chunks(blocks_per_thread * block_size)
+                .map(|run| scope.spawn(move || digest_run(run)))
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    #[cfg(not(feature = "std"))]
+    digest_run(data)
+}
+
+fn parallelhash(
+    mode: Mode,
+    data: &[u8],
+    block_size: usize,
+    out_len: usize,
+    custom: &[u8],
+    threads: usize,
+) -> Vec<u8> {
+    assert!(block_size > 0, "block size must be greater than zero");
+
+    let mut sponge = Sponge::new_cshake(mode, b"ParallelHash", custom).unwrap();
+    let block_count = data.len().div_ceil(block_size);
+
+    sponge.update(&left_encode(block_size as u64));
+    sponge.update(&block_digests(mode, data, block_size, threads));
+    sponge.update(&right_encode(block_count as u64));
+    sponge.update(&right_encode(out_len as u64 * 8));
+    sponge.absorb_padding();
+
+    sponge.squeeze_xof(out_len)
+}
+
+#[cfg(feature = "std")]
+fn available_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+#[cfg(not(feature = "std"))]
+fn available_threads() -> usize {
+    1
+}
+
+/// ParallelHash128 from NIST SP 800-185. `data` is split into `block_size`-byte blocks
+/// which are hashed concurrently, one run of blocks per available core.
+///
+/// Panics if `block_size` is zero.
+pub fn parallelhash128(data: &[u8], block_size: usize, out_len: usize, custom: &[u8]) -> Vec<u8> {
+    parallelhash(
+        Mode::CShake128,
+        data,
+        block_size,
+        out_len,
+        custom,
+        available_threads(),
+    )
+}
+
+/// ParallelHash256 from NIST SP 800-185, see [`parallelhash128`].
+pub fn parallelhash256(data: &[u8], block_size: usize, out_len: usize, custom: &[u8]) -> Vec<u8> {
+    parallelhash(
+        Mode::CShake256,
+        data,
+        block_size,
+        out_len,
+        custom,
+        available_threads(),
+    )
+}
+
+/// Like [`parallelhash128`], but spreads the blocks over `threads` threads rather than one
+/// per available core. With `threads` at 1, or without the `std` feature, every block is
+/// hashed on the calling thread. The digest is the same either way.
+///
+/// Panics if `block_size` is zero.
+pub fn parallelhash128_with_threads(
+    data: &[u8],
+    block_size: usize,
+    out_len: usize,
+    custom: &[u8],
+    threads: usize,
+) -> Vec<u8> {
+    parallelhash(Mode::CShake128, data, block_size, out_len, custom, threads)
+}
+
+/// ParallelHash256 on an explicit number of threads, see [`parallelhash128_with_threads`].
+pub fn parallelhash256_with_threads(
+    data: &[u8],
+    block_size: usize,
+    out_len: usize,
+    custom: &[u8],
+    threads: usize,
+) -> Vec<u8> {
+    parallelhash(Mode::CShake256, data, block_size, out_len, custom, threads)
+}
@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+
+use crate::{Mode, Sponge};
+
+// K12 leaves and the first chunk of the final node are this many bytes
+const CHUNK_SIZE: usize = 8192;
+
+// TurboSHAKE is SHAKE with 12 rounds and a caller-chosen domain byte (0x01..=0x7f)
+pub(crate) fn turboshake_sponge(mode: Mode, domain: u8) -> Sponge {
+    let mut sponge = Sponge::new(mode);
+    sponge.suffix = domain;
+    sponge.rounds = 12;
+    sponge
+}
+
+// big-endian bytes of `value` with leading zeros dropped, followed by their count
+fn length_encode(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first = bytes.iter().position(|&byte| byte != 0).unwrap_or(8);
+
+    let mut encoded = bytes[first..].to_vec();
+    encoded.push(encoded.len() as u8);
+    encoded
+}
+
+// feeds bytes `start..end` of the concatenation of `parts` into the sponge
+fn update_range(sponge: &mut Sponge, parts: &[&[u8]], start: usize, end: usize) {
+    let mut offset = 0;
+
+    for part in parts {
+        let from = start.clamp(offset, offset + part.len()) - offset;
+        let to = end.clamp(offset, offset + part.len()) - offset;
+        sponge.update(&part[from..to]);
+        offset += part.len();
+    }
+}
+
+fn turboshake(mode: Mode, data: &[u8], domain: u8, out_len: usize) -> Vec<u8> {
+    assert!(
+        (0x01..=0x7f).contains(&domain),
+        "TurboSHAKE domain byte must be in 0x01..=0x7f"
+    );
+
+    let mut sponge = turboshake_sponge(mode, domain);
+    sponge.absorb_bytes(data);
+    sponge.squeeze_xof(out_len)
+}
+
+/// TurboSHAKE128: SHAKE128 with 12 rounds and a domain separation byte in `0x01..=0x7f`
+/// (`0x1f` when there is nothing to separate).
+///
+/// Panics if `domain` is out of range.
+pub fn turboshake128(data: &[u8], domain: u8, out_len: usize) -> Vec<u8> {
+    turboshake(Mode::Shake128, data, domain, out_len)
+}
+
+/// TurboSHAKE256, see [`turboshake128`].
+pub fn turboshake256(data: &[u8], domain: u8, out_len: usize) -> Vec<u8> {
+    turboshake(Mode::Shake256, data, domain, out_len)
+}
+
+/// KangarooTwelve: `out_len` bytes of output over `data` with an optional customization
+/// string, hashing 8192-byte chunks as independent leaves of a tree.
+pub fn k12(data: &[u8], customization: &[u8], out_len: usize) -> Vec<u8> {
+    let encoded_len = length_encode(customization.len() as u64);
+    let parts = [data, customization, &encoded_len];
+    let total_len = data.len() + customization.len() + encoded_len.len();
+
+    if total_len <= CHUNK_SIZE {
+        let mut sponge = turboshake_sponge(Mode::Shake128, 0x07);
+        update_range(&mut sponge, &parts, 0, total_len);
+        sponge.absorb_padding();
+        return sponge.squeeze_xof(out_len);
+    }
+
+    let mut final_node = turboshake_sponge(Mode::Shake128, 0x06);
+    update_range(&mut final_node, &parts, 0, CHUNK_SIZE);
+    final_node.update(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut leaf_count = 0;
+    let mut start = CHUNK_SIZE;
+    while start < total_len {
+        let end = (start + CHUNK_SIZE).min(total_len);
+
+        let mut leaf = turboshake_sponge(Mode::Shake128, 0x0b);
+        update_range(&mut leaf, &parts, start, end);
+        leaf.absorb_padding();
+        final_node.update(&leaf.squeeze_xof(32));
+
+        leaf_count += 1;
+        start = end;
+    }
+
+    final_node.update(&length_encode(leaf_count));
+    final_node.update(&[0xff, 0xff]);
+    final_node.absorb_padding();
+
+    final_node.squeeze_xof(out_len)
+}
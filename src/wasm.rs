@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{Mode, Sponge, to_hex};
+
+/// The SHA3-256 digest of `bytes` as lowercase hex. Exported to JavaScript as `sha3_256`;
+/// a `Uint8Array` is passed without copying it through a string first.
+#[wasm_bindgen(js_name = sha3_256)]
+pub fn sha3_256_hex(bytes: &[u8]) -> String {
+    to_hex(&crate::sha3_256(bytes))
+}
+
+/// A streaming sponge for JavaScript: feed it `Uint8Array`s with `update`, then read the
+/// digest with `digest` or `hex_digest`, which also reset it for the next message.
+#[wasm_bindgen]
+pub struct WasmSponge {
+    sponge: Sponge,
+}
+
+#[wasm_bindgen]
+impl WasmSponge {
+    /// Creates a sponge for `mode`, named as on the command line (`"sha3-256"`,
+    /// `"shake128"`, ...). Throws on an unknown mode.
+    #[wasm_bindgen(constructor)]
+    pub fn new(mode: &str) -> Result<WasmSponge, JsError> {
+        let mode = Mode::try_from(mode).map_err(|error| JsError::new(&error.to_string()))?;
+        Ok(WasmSponge {
+            sponge: Sponge::new(mode),
+        })
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.sponge.update(data);
+    }
+
+    /// The digest of everything passed to `update`, at the mode's default length.
+    pub fn digest(&mut self) -> Vec<u8> {
+        let mut digest = vec![0; self.sponge.output_len];
+        self.sponge.finalize_and_reset(&mut digest);
+        digest
+    }
+
+    /// Like `digest`, as lowercase hex.
+    pub fn hex_digest(&mut self) -> String {
+        to_hex(&self.digest())
+    }
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "alloc")]
+
+use sha3sum::{Mode, Sha3Error, Sponge, SpongeBuilder};
+
+fn finish(mut sponge: Sponge, data: &[u8]) -> Vec<u8> {
+    sponge.update(data);
+    sponge.finalize()
+}
+
+#[test]
+fn defaults_match_sponge_new() {
+    let sponge = SpongeBuilder::new().build().unwrap();
+    assert_eq!(
+        sponge.buffer_size(),
+        Sponge::new(Mode::default()).buffer_size()
+    );
+    assert_eq!(
+        finish(sponge, b"abc"),
+        finish(Sponge::new(Mode::default()), b"abc")
+    );
+
+    let sponge = Sponge::builder().mode(Mode::Keccak256).build().unwrap();
+    assert_eq!(
+        finish(sponge, b"abc"),
+        finish(Sponge::new(Mode::Keccak256), b"abc")
+    );
+}
+
+#[test]
+fn settings_reach_the_sponge() {
+    let sponge = Sponge::builder()
+        .mode(Mode::Shake256)
+        .output_len(100)
+        .buffer_size(1)
+        .build()
+        .unwrap();
+    assert_eq!(sponge.buffer_size(), Mode::Shake256.rate());
+
+    let mut expected = Sponge::new(Mode::Shake256);
+    expected.update(b"abc");
+    assert_eq!(finish(sponge, b"abc"), {
+        let mut output = vec![0; 100];
+        expected.finalize_into(&mut output);
+        output
+    });
+
+    let sponge = Sponge::builder()
+        .mode(Mode::CShake256)
+        .function_name(b"name")
+        .customization(b"custom")
+        .build()
+        .unwrap();
+    assert_eq!(
+        finish(sponge, b"abc"),
+        finish(
+            Sponge::new_cshake(Mode::CShake256, b"name", b"custom").unwrap(),
+            b"abc"
+        )
+    );
+}
+
+#[test]
+fn settings_the_mode_lacks_are_errors() {
+    assert!(matches!(
+        Sponge::builder()
+            .mode(Mode::Sha3_256)
+            .output_len(16)
+            .build(),
+        Err(Sha3Error::InvalidParameters(_))
+    ));
+    assert!(matches!(
+        Sponge::builder()
+            .mode(Mode::Shake128)
+            .customization(b"custom")
+            .build(),
+        Err(Sha3Error::InvalidMode(_))
+    ));
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize_can_be_turned_off() {
+    let sponge = Sponge::builder()
+        .mode(Mode::Sha3_512)
+        .zeroize(false)
+        .build()
+        .unwrap();
+    assert_eq!(finish(sponge, b"abc"), sha3sum::sha3_512(b"abc"));
+}
@@ -0,0 +1,654 @@
+// runs the sha3sum binary itself, for options that only exist on the command line
+#![cfg(feature = "std")]
+
+mod common;
+
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+use common::pattern;
+use sha3sum::{Mode, Sponge, to_base64};
+
+// a directory of its own for each test, removed again even when the test fails
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let dir = env::temp_dir().join(format!("sha3sum-test-{}-cli-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Scratch(dir)
+    }
+
+    // writes `contents` to `name` inside the directory and returns its path
+    fn file(&self, name: &str, contents: &[u8]) -> String {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+struct Outcome {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+// runs sha3sum with `stdin` piped in, NO_COLOR cleared, and `envs` set on top
+fn invoke_with(args: &[&str], stdin: &[u8], envs: &[(&str, &str)]) -> Outcome {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sha3sum"))
+        .args(args)
+        .env_remove("NO_COLOR")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    Outcome {
+        success: output.status.success(),
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+    }
+}
+
+fn invoke(args: &[&str]) -> Outcome {
+    invoke_with(args, b"", &[])
+}
+
+fn digest(mode: Mode, data: &[u8]) -> String {
+    let mut sponge = Sponge::new(mode);
+    sponge.absorb_bytes(data);
+    sponge.squeeze()
+}
+
+fn sha3_256(data: &[u8]) -> String {
+    digest(Mode::Sha3_256, data)
+}
+
+#[test]
+fn check_reports_ok_and_failed() {
+    let scratch = Scratch::new("check");
+    let good = scratch.file("good", b"good");
+    let bad = scratch.file("bad", b"bad");
+    let list = scratch.file(
+        "list",
+        format!(
+            "{}  {}\n{}  {}\n",
+            sha3_256(b"good"),
+            good,
+            sha3_256(b"other"),
+            bad
+        )
+        .as_bytes(),
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", &list]);
+    assert!(!outcome.success);
+    assert_eq!(outcome.stdout, format!("{}: OK\n{}: FAILED\n", good, bad));
+    assert!(
+        outcome
+            .stderr
+            .contains("WARNING: 1 computed checksum did NOT match")
+    );
+
+    let list = scratch.file(
+        "all-good",
+        format!("{}  {}\n", sha3_256(b"good"), good).as_bytes(),
+    );
+    let outcome = invoke(&["-m", "256", "-c", &list]);
+    assert!(outcome.success);
+    assert_eq!(outcome.stdout, format!("{}: OK\n", good));
+    assert_eq!(outcome.stderr, "");
+
+    // uppercase digests and the binary indicator check the same
+    let list = scratch.file(
+        "upper",
+        format!("{} *{}\n", sha3_256(b"good").to_uppercase(), good).as_bytes(),
+    );
+    assert!(invoke(&["-m", "256", "-c", &list]).success);
+}
+
+#[test]
+fn tagged_output_checks_back_in_any_mode() {
+    let scratch = Scratch::new("tag");
+    let file = scratch.file("data", &pattern(1000));
+
+    let outcome = invoke(&["--tag", "-m", "512", &file]);
+    assert!(outcome.success);
+    assert_eq!(
+        outcome.stdout,
+        format!(
+            "SHA3-512 ({}) = {}\n",
+            file,
+            digest(Mode::Sha3_512, &pattern(1000))
+        )
+    );
+
+    // the tag names the mode, so checking needs no -m
+    let list = scratch.file("list", outcome.stdout.as_bytes());
+    let outcome = invoke(&["-c", &list]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, format!("{}: OK\n", file));
+}
+
+#[test]
+fn binary_text_and_zero_shape_the_lines() {
+    let scratch = Scratch::new("indicator");
+    let file = scratch.file("data", b"abc");
+    let digest = sha3_256(b"abc");
+
+    assert_eq!(
+        invoke(&["-m", "256", "-b", &file]).stdout,
+        format!("{} *{}\n", digest, file)
+    );
+    assert_eq!(
+        invoke(&["-m", "256", "-bt", &file]).stdout,
+        format!("{}  {}\n", digest, file)
+    );
+
+    let outcome = invoke(&["-m", "256", "-z", &file]);
+    assert_eq!(outcome.stdout, format!("{}  {}\0", digest, file));
+
+    // -z lists are NUL-terminated, so a name may hold a newline
+    let odd = scratch.file("line\nbreak", b"abc");
+    let list = scratch.file("list", format!("{}  {}\0", digest, odd).as_bytes());
+    let outcome = invoke(&["-m", "256", "-c", "-z", &list]);
+    assert!(outcome.success, "{}", outcome.stderr);
+}
+
+#[test]
+fn quiet_and_status_cut_the_check_output() {
+    let scratch = Scratch::new("quiet");
+    let good = scratch.file("good", b"good");
+    let bad = scratch.file("bad", b"bad");
+    let list = scratch.file(
+        "list",
+        format!(
+            "{}  {}\n{}  {}\n",
+            sha3_256(b"good"),
+            good,
+            sha3_256(b"other"),
+            bad
+        )
+        .as_bytes(),
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", "--quiet", &list]);
+    assert!(!outcome.success);
+    assert_eq!(outcome.stdout, format!("{}: FAILED\n", bad));
+
+    let outcome = invoke(&["-m", "256", "-c", "--status", &list]);
+    assert!(!outcome.success);
+    assert_eq!(outcome.stdout, "");
+    assert_eq!(outcome.stderr, "");
+
+    let list = scratch.file(
+        "all-good",
+        format!("{}  {}\n", sha3_256(b"good"), good).as_bytes(),
+    );
+    let outcome = invoke(&["-m", "256", "-c", "--status", &list]);
+    assert!(outcome.success);
+    assert_eq!(outcome.stdout, "");
+}
+
+#[test]
+fn ignore_missing_skips_only_missing_files() {
+    let scratch = Scratch::new("missing");
+    let good = scratch.file("good", b"good");
+    let missing = scratch
+        .path()
+        .join("missing")
+        .to_string_lossy()
+        .into_owned();
+    let list = scratch.file(
+        "list",
+        format!("{0}  {1}\n{0}  {2}\n", sha3_256(b"good"), good, missing).as_bytes(),
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", &list]);
+    assert!(!outcome.success);
+    assert!(
+        outcome
+            .stdout
+            .contains(&format!("{}: FAILED open or read", missing))
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", "--ignore-missing", &list]);
+    assert!(outcome.success);
+    assert_eq!(outcome.stdout, format!("{}: OK\n", good));
+
+    // skipping every listed file verifies nothing, which is a failure
+    let list = scratch.file(
+        "only-missing",
+        format!("{}  {}\n", sha3_256(b""), missing).as_bytes(),
+    );
+    let outcome = invoke(&["-m", "256", "-c", "--ignore-missing", &list]);
+    assert!(!outcome.success);
+    assert!(outcome.stderr.contains("no file was verified"));
+}
+
+#[test]
+fn strict_fails_improperly_formatted_lines() {
+    let scratch = Scratch::new("strict");
+    let good = scratch.file("good", b"good");
+    let list = scratch.file(
+        "list",
+        format!("{}  {}\nnot a checksum line\n", sha3_256(b"good"), good).as_bytes(),
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", &list]);
+    assert!(outcome.success);
+    assert!(
+        outcome
+            .stderr
+            .contains("WARNING: 1 line is improperly formatted")
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", "--strict", &list]);
+    assert!(!outcome.success);
+
+    // a list with nothing usable in it fails even without --strict
+    let list = scratch.file("garbage", b"nothing to see\n");
+    let outcome = invoke(&["-c", &list]);
+    assert!(!outcome.success);
+    assert!(
+        outcome
+            .stderr
+            .contains("no properly formatted checksum lines found")
+    );
+}
+
+#[test]
+fn digests_of_the_wrong_length_are_improperly_formatted() {
+    let scratch = Scratch::new("truncated");
+    let good = scratch.file("good", b"good");
+    let digest = sha3_256(b"good");
+    let list = scratch.file(
+        "list",
+        format!(
+            "{0}  {1}\n{2}  {1}\nSHA3-512 ({1}) = {0}\n",
+            digest,
+            good,
+            &digest[..60]
+        )
+        .as_bytes(),
+    );
+
+    let outcome = invoke(&["-m", "256", "-c", &list]);
+    assert!(outcome.success);
+    assert_eq!(outcome.stdout, format!("{}: OK\n", good));
+    assert!(
+        outcome
+            .stderr
+            .contains("WARNING: 2 lines are improperly formatted")
+    );
+
+    assert!(!invoke(&["-m", "256", "-c", "--strict", &list]).success);
+}
+
+// a file that can't be read is skipped but fails the run; a bad mode fails before hashing
+#[test]
+fn unreadable_files_and_bad_modes_fail() {
+    let scratch = Scratch::new("failures");
+    let good = scratch.file("good", b"good");
+    let missing = scratch
+        .path()
+        .join("missing")
+        .to_string_lossy()
+        .into_owned();
+
+    let outcome = invoke(&["-m", "256", &missing, &good]);
+    assert!(!outcome.success);
+    assert_eq!(outcome.stdout, format!("{}  {}\n", sha3_256(b"good"), good));
+    assert!(outcome.stderr.contains(&missing));
+
+    let outcome = invoke(&["-m", "999", &good]);
+    assert!(!outcome.success);
+    assert_eq!(outcome.stdout, "");
+}
+
+#[test]
+fn repeated_modes_label_each_line() {
+    let scratch = Scratch::new("modes");
+    let file = scratch.file("data", b"abc");
+
+    let outcome = invoke(&["-m", "256", "-m", "512", "-m", "256", &file]);
+    assert_eq!(
+        outcome.stdout,
+        format!(
+            "sha3-256: {0}  {2}\nsha3-512: {1}  {2}\n",
+            sha3_256(b"abc"),
+            digest(Mode::Sha3_512, b"abc"),
+            file
+        )
+    );
+
+    // the labels say which mode to check each line with
+    let list = scratch.file("list", outcome.stdout.as_bytes());
+    let outcome = invoke(&["-c", &list]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, format!("{0}: OK\n{0}: OK\n", file));
+}
+
+#[cfg(unix)]
+#[test]
+fn recursion_skips_symlinks_and_catches_loops() {
+    let scratch = Scratch::new("recursive");
+    fs::create_dir(scratch.path().join("sub")).unwrap();
+    let top = scratch.file("a", b"a");
+    let nested = scratch.file("sub/b", b"b");
+    std::os::unix::fs::symlink(scratch.path(), scratch.path().join("sub/up")).unwrap();
+    let root = scratch.path().to_string_lossy().into_owned();
+
+    let expected = format!(
+        "{}  {}\n{}  {}\n",
+        sha3_256(b"a"),
+        top,
+        sha3_256(b"b"),
+        nested
+    );
+
+    let outcome = invoke(&["-m", "256", "-r", &root]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, expected);
+
+    // following the link leads back to the top, which is reported rather than walked again
+    let outcome = invoke(&["-m", "256", "-r", "-L", &root]);
+    assert!(!outcome.success);
+    assert!(outcome.stderr.contains("File system loop detected"));
+    assert_eq!(outcome.stdout, expected);
+}
+
+#[cfg(feature = "glob")]
+#[test]
+fn unexpanded_patterns_expand_in_sorted_order() {
+    let scratch = Scratch::new("glob");
+    let c = scratch.file("c.txt", b"c");
+    let a = scratch.file("a.txt", b"a");
+    scratch.file("b.log", b"b");
+    let pattern = scratch.path().join("*.txt").to_string_lossy().into_owned();
+
+    let outcome = invoke(&["-m", "256", &pattern]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(
+        outcome.stdout,
+        format!("{}  {}\n{}  {}\n", sha3_256(b"a"), a, sha3_256(b"c"), c)
+    );
+}
+
+#[test]
+fn file_lists_name_the_files_to_hash() {
+    let scratch = Scratch::new("files-from");
+    let first = scratch.file("first", b"1");
+    let second = scratch.file("second", b"2");
+    let expected = format!(
+        "{}  {}\n{}  {}\n",
+        sha3_256(b"1"),
+        first,
+        sha3_256(b"2"),
+        second
+    );
+
+    let list = scratch.file("list", format!("{}\n\n{}\n", first, second).as_bytes());
+    assert_eq!(
+        invoke(&["-m", "256", "--files-from", &list]).stdout,
+        expected
+    );
+
+    let outcome = invoke_with(
+        &["-m", "256", "--filenames0"],
+        format!("{}\0{}\0", first, second).as_bytes(),
+        &[],
+    );
+    assert_eq!(outcome.stdout, expected);
+}
+
+#[test]
+fn json_escapes_file_names() {
+    let scratch = Scratch::new("json");
+    let file = scratch.file("quote\"back\\slash\ttab", b"abc");
+
+    let outcome = invoke(&["-m", "256", "--json", &file]);
+    assert!(outcome.success);
+    let entries: serde_json::Value = serde_json::from_str(&outcome.stdout).unwrap();
+    assert_eq!(
+        entries,
+        serde_json::json!([{"file": file, "algorithm": "sha3-256", "hash": sha3_256(b"abc")}])
+    );
+
+    let empty = scratch.file("empty-list", b"");
+    assert_eq!(invoke(&["--json", "--files-from", &empty]).stdout, "[]\n");
+}
+
+#[test]
+fn digest_encodings() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_bytes(b"abc");
+
+    let upper = invoke_with(&["-m", "256", "-u"], b"abc", &[]);
+    assert_eq!(upper.stdout, format!("{}  -\n", sponge.squeeze_upper()));
+
+    let base64 = invoke_with(&["-m", "256", "--base64"], b"abc", &[]);
+    assert_eq!(
+        base64.stdout,
+        format!("{}  -\n", to_base64(&sponge.squeeze_bytes()))
+    );
+}
+
+#[test]
+fn jobs_keep_the_order_files_were_given_in() {
+    let scratch = Scratch::new("jobs");
+    // larger files first, so later ones tend to finish sooner
+    let files: Vec<String> = (0..12)
+        .map(|index| scratch.file(&index.to_string(), &pattern((12 - index) * 50_000)))
+        .collect();
+    let mut args = vec!["-m", "256"];
+    args.extend(files.iter().map(String::as_str));
+
+    let sequential = invoke(&args);
+    args.extend(["-j", "4"]);
+    let parallel = invoke(&args);
+    args.extend(["-j", "0"]);
+
+    assert!(parallel.success);
+    assert_eq!(parallel.stdout, sequential.stdout);
+    assert_eq!(invoke(&args).stdout, sequential.stdout);
+}
+
+#[test]
+fn progress_stays_off_a_piped_stdout() {
+    let scratch = Scratch::new("progress");
+    let file = scratch.file("data", &pattern(3 << 20));
+
+    let plain = invoke(&["-m", "256", &file]);
+    let outcome = invoke(&["-m", "256", "--progress", &file]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, plain.stdout);
+    assert_eq!(outcome.stderr, "");
+}
+
+#[test]
+fn timing_is_reported_on_stderr() {
+    let scratch = Scratch::new("timing");
+    let file = scratch.file("data", b"abc");
+
+    let outcome = invoke(&["-m", "256", "--timing", &file]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, format!("{}  {}\n", sha3_256(b"abc"), file));
+
+    let lines: Vec<&str> = outcome.stderr.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", outcome.stderr);
+    assert!(lines[0].starts_with(&format!("sha3sum: {}: 3 bytes in ", file)));
+    assert!(lines[1].starts_with("sha3sum: total: 3 bytes in "));
+    assert!(lines.iter().all(|line| line.ends_with(" MB/s)")));
+}
+
+#[test]
+fn color_follows_the_flag_and_no_color() {
+    let scratch = Scratch::new("color");
+    let good = scratch.file("good", b"good");
+    let list = scratch.file(
+        "list",
+        format!("{}  {}\n", sha3_256(b"good"), good).as_bytes(),
+    );
+    let painted = format!("{}: \x1b[32mOK\x1b[0m\n", good);
+    let plain = format!("{}: OK\n", good);
+
+    let check = |color: &str, envs: &[(&str, &str)]| {
+        invoke_with(&["-m", "256", "-c", color, &list], b"", envs).stdout
+    };
+
+    assert_eq!(check("--color", &[]), painted);
+    assert_eq!(check("--color=always", &[("NO_COLOR", "1")]), painted);
+    assert_eq!(check("--color=never", &[]), plain);
+    // stdout is a pipe here, so auto never colors, with or without NO_COLOR
+    assert_eq!(check("--color=auto", &[]), plain);
+    assert_eq!(check("--color=auto", &[("NO_COLOR", "1")]), plain);
+}
+
+#[test]
+fn concat_hashes_the_files_as_one_input() {
+    let scratch = Scratch::new("concat");
+    let first = scratch.file("first", b"hello, ");
+    let second = scratch.file("second", b"world");
+
+    let outcome = invoke(&["-m", "256", "--concat", &first, &second]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(
+        outcome.stdout,
+        format!("{}  {} + {}\n", sha3_256(b"hello, world"), first, second)
+    );
+
+    // nothing marks where one file ends, so only the order of the bytes counts
+    let swapped = invoke(&["-m", "256", "--concat", &second, &first]);
+    assert_eq!(
+        swapped.stdout,
+        format!("{}  {} + {}\n", sha3_256(b"worldhello, "), second, first)
+    );
+    assert_ne!(sha3_256(b"worldhello, "), sha3_256(b"hello, world"));
+}
+
+#[test]
+fn skip_and_length_hash_a_slice() {
+    let scratch = Scratch::new("range");
+    let data = pattern(1000);
+    let file = scratch.file("data", &data);
+
+    // the file is seeked past the skipped bytes, standard input reads through them
+    for (skip, length, slice) in [
+        (0, Some(10), &data[..10]),
+        (100, None, &data[100..]),
+        (137, Some(300), &data[137..437]),
+        (900, Some(300), &data[900..]),
+        (1500, Some(10), &data[..0]),
+    ] {
+        let skip = skip.to_string();
+        let length = length.map(|length: usize| length.to_string());
+        let mut args = vec!["-m", "256", "--skip", &skip];
+        if let Some(length) = &length {
+            args.extend(["--length", length]);
+        }
+
+        let outcome = invoke(&[&args[..], &[&file]].concat());
+        assert!(outcome.success, "{:?}: {}", args, outcome.stderr);
+        assert_eq!(outcome.stdout, format!("{}  {}\n", sha3_256(slice), file));
+
+        let outcome = invoke_with(&args, &data, &[]);
+        assert!(outcome.success, "{:?}: {}", args, outcome.stderr);
+        assert_eq!(
+            outcome.stdout,
+            format!("{}  -\n", sha3_256(slice)),
+            "{:?} of standard input",
+            args
+        );
+    }
+}
+
+#[test]
+fn a_closed_pipe_ends_the_run_quietly() {
+    let scratch = Scratch::new("closed-pipe");
+    let file = scratch.file("data", b"abc");
+
+    // far more output than a pipe holds, so sha3sum is still writing when the reader goes away
+    let mut args = vec!["-m", "256"];
+    args.extend(vec![file.as_str(); 5000]);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sha3sum"))
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut first = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut first)
+        .unwrap();
+    assert_eq!(first, format!("{}  {}\n", sha3_256(b"abc"), file));
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[cfg(feature = "checkpoint")]
+#[test]
+fn resuming_keeps_saving_to_the_checkpoint_it_came_from() {
+    let scratch = Scratch::new("resume");
+    let data = pattern(100_000);
+    let file = scratch.file("data", &data);
+
+    // a checkpoint as an interrupted run would have left it, 40000 bytes in
+    let checkpoint = |name| {
+        let mut sponge = Sponge::new(Mode::Sha3_256);
+        sponge.update(&data[..40_000]);
+        let saved = serde_json::json!({
+            "file": file,
+            "mode": Mode::Sha3_256,
+            "offset": 40_000,
+            "state": sponge.snapshot(),
+        });
+        scratch.file(name, saved.to_string().as_bytes())
+    };
+    let expected = format!("{}  {}\n", sha3_256(&data), file);
+
+    let resumed = checkpoint("resumed");
+    let outcome = invoke(&["--resume", &resumed, "--checkpoint-interval", "16K"]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, expected);
+    assert!(!Path::new(&resumed).exists());
+
+    // naming another checkpoint saves there instead and leaves the one resumed from alone
+    let resumed = checkpoint("resumed");
+    let elsewhere = scratch.path().join("elsewhere");
+    let elsewhere = elsewhere.to_string_lossy();
+    let outcome = invoke(&[
+        "--resume",
+        &resumed,
+        "--checkpoint",
+        &elsewhere,
+        "--checkpoint-interval",
+        "16K",
+    ]);
+    assert!(outcome.success, "{}", outcome.stderr);
+    assert_eq!(outcome.stdout, expected);
+    assert!(Path::new(&resumed).exists());
+    assert!(!Path::new(elsewhere.as_ref()).exists());
+}
@@ -0,0 +1,10 @@
+// helpers shared by the integration tests; each test crate uses only some of them
+#![allow(dead_code)]
+
+pub mod reference;
+
+// every input is the byte pattern `i % 251`, so no run of it lines up with a block; it is also
+// the `ptn` the KangarooTwelve and TurboSHAKE test vectors are built from
+pub fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
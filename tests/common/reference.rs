@@ -0,0 +1,41 @@
+// the `sha3` crate as an independent reference; shared with the fuzz target, so it uses nothing
+// but `sha3` and `sha3sum`
+
+use sha3::digest::{Digest, ExtendableOutput, Update};
+use sha3sum::Mode;
+
+// every mode the `sha3` crate also implements; it has no RawSHAKE
+pub const MODES: [Mode; 10] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+    Mode::Keccak256,
+    Mode::Keccak512,
+    Mode::Shake128,
+    Mode::Shake256,
+    Mode::CShake128,
+    Mode::CShake256,
+];
+
+pub fn xof<H: Default + Update + ExtendableOutput>(data: &[u8], len: usize) -> Vec<u8> {
+    let mut hasher = H::default();
+    hasher.update(data);
+    hasher.finalize_boxed(len).into_vec()
+}
+
+// the `sha3` crate's digest of `data` in `mode`, at the length sha3sum squeezes by default
+pub fn reference(mode: Mode, data: &[u8]) -> Vec<u8> {
+    match mode {
+        Mode::Sha3_224 => sha3::Sha3_224::digest(data).to_vec(),
+        Mode::Sha3_256 => sha3::Sha3_256::digest(data).to_vec(),
+        Mode::Sha3_384 => sha3::Sha3_384::digest(data).to_vec(),
+        Mode::Sha3_512 => sha3::Sha3_512::digest(data).to_vec(),
+        Mode::Keccak256 => sha3::Keccak256::digest(data).to_vec(),
+        Mode::Keccak512 => sha3::Keccak512::digest(data).to_vec(),
+        // cSHAKE with no function name or customization string is SHAKE
+        Mode::Shake128 | Mode::CShake128 => xof::<sha3::Shake128>(data, mode.output_len()),
+        Mode::Shake256 | Mode::CShake256 => xof::<sha3::Shake256>(data, mode.output_len()),
+        Mode::RawShake128 | Mode::RawShake256 => unreachable!(),
+    }
+}
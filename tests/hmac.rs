@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+
+use sha3sum::{Mode, hmac, to_hex};
+
+const MODES: [Mode; 4] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+];
+
+// keys and messages from RFC 4231's HMAC-SHA2 cases, plus a key longer than every SHA-3 rate and
+// one exactly as long as the SHA3-224 block; the digests are HMAC-SHA3-224, -256, -384 and -512
+fn cases() -> Vec<(Vec<u8>, &'static [u8], [&'static str; 4])> {
+    vec![
+        (
+            vec![0x0b; 20],
+            b"Hi There",
+            [
+                "3b16546bbc7be2706a031dcafd56373d9884367641d8c59af3c860f7",
+                "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb",
+                "68d2dcf7fd4ddd0a2240c8a437305f61fb7334cfb5d0226e1bc27dc10a2e723a20d370b47743130e26ac7e3d532886bd",
+                "eb3fbd4b2eaab8f5c504bd3a41465aacec15770a7cabac531e482f860b5ec7ba47ccb2c6f2afce8f88d22b6dc61380f23a668fd3888bb80537c0a0b86407689e",
+            ],
+        ),
+        (
+            b"Jefe".to_vec(),
+            b"what do ya want for nothing?",
+            [
+                "7fdb8dd88bd2f60d1b798634ad386811c2cfc85bfaf5d52bbace5e66",
+                "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5",
+                "f1101f8cbf9766fd6764d2ed61903f21ca9b18f57cf3e1a23ca13508a93243ce48c045dc007f26a21b3f5e0e9df4c20a",
+                "5a4bfeab6166427c7a3647b747292b8384537cdb89afb3bf5665e4c5e709350b287baec921fd7ca0ee7a0c31d022a95e1fc92ba9d77df883960275beb4e62024",
+            ],
+        ),
+        (
+            vec![0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            [
+                "b4a1f04c00287a9b7f6075b313d279b833bc8f75124352d05fb9995f",
+                "ed73a374b96c005235f948032f09674a58c0ce555cfc1f223b02356560312c3b",
+                "0fc19513bf6bd878037016706a0e57bc528139836b9a42c3d419e498e0e1fb9616fd669138d33a1105e07c72b6953bcc",
+                "00f751a9e50695b090ed6911a4b65524951cdc15a73a5d58bb55215ea2cd839ac79d2b44a39bafab27e83fde9e11f6340b11d991b1b91bf2eee7fc872426c3a4",
+            ],
+        ),
+        (
+            vec![0xaa; 200],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            [
+                "5e73d57bd011f0f92fef3c3b92ea4bcb4821c6d83c37db34f29e0760",
+                "49ad92b02124fdac9627ae45e008a696182ab6bfb8470457777c744aeb9df06f",
+                "3e7b62d091d75f484892bc2ed26d7b0ed37c9529f0227197cc8522971eb6f7215dd4e0cc6ea306987e0cbfe914f3a916",
+                "fafc7b7fe3332ce153966b27f6586fa5b49ec5d8dff3d7fd26a011451ca4c9de437913879159d9c5181a9a6f377ef18b48399756decea695b04fe90a9d3b93d1",
+            ],
+        ),
+        (
+            (0..144).collect(),
+            b"key exactly one SHA3-224 block",
+            [
+                "4a885298e5b689689d854db9d8441315dc9e31090c64346fcfa0fe0e",
+                "9646b3ad398b26849b080f392f95f49cf52c6d44523a95364be87def5dc2ccbf",
+                "d402a4bfc4ad8a1554e9b812001dea3fde7cbf786cde1be7826e30ac57d269a50bb9dcbb4a57d0d6cd18fdfef53fd6aa",
+                "d8ab1f38ce0782c7ed4786fd5a32b8f40e902e6cef6c5e18fef7482c1a8c172036a3805ffb205a199b81f44348efc94fa764aa890e5383b86a684c17ed17611a",
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn hmac_sha3_known_answers() {
+    for (key, data, digests) in cases() {
+        for (mode, expected) in MODES.into_iter().zip(digests) {
+            assert_eq!(
+                to_hex(&hmac(mode, &key, data)),
+                expected,
+                "{} with a {}-byte key",
+                mode,
+                key.len()
+            );
+        }
+    }
+}
+
+#[test]
+fn hmac_is_keyed() {
+    let data = b"message";
+    assert_ne!(
+        hmac(Mode::Sha3_256, b"key", data),
+        hmac(Mode::Sha3_256, b"kez", data)
+    );
+    assert_eq!(
+        hmac(Mode::Shake128, b"key", data).len(),
+        Mode::Shake128.output_len()
+    );
+}
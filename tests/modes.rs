@@ -0,0 +1,739 @@
+mod common;
+
+#[cfg(feature = "std")]
+use std::{env, fs, process};
+
+use common::pattern;
+#[cfg(feature = "alloc")]
+use sha3sum::MultiSponge;
+#[cfg(feature = "std")]
+use sha3sum::Sha3Error;
+use sha3sum::{Mode, Sponge};
+
+#[cfg(feature = "alloc")]
+const MODES: [Mode; 4] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+];
+
+// inputs of 0 and 1 bytes, one short of the rate, exactly the rate, one past it, and a few MiB
+fn sizes(mode: Mode) -> [usize; 6] {
+    let rate = mode.rate();
+    [0, 1, rate - 1, rate, rate + 1, 3 * 1024 * 1024 + 5]
+}
+
+// the default-length digest of each size from `sizes`, from an independent implementation
+const REFERENCE: [(Mode, [&str; 6]); 12] = [
+    (
+        Mode::Sha3_224,
+        [
+            "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+            "bdd5167212d2dc69665f5a8875ab87f23d5ce7849132f56371a19096",
+            "64d0e8a1be3cf30ef6727b30a6e428f7f068d44634c943d277ad8e7f",
+            "5be75e6a08f19913a1d8036c056cc4556b98dc90aeca3f2a0664dedc",
+            "90b861ac1b1598459ad8337afa9933ce2f1a6f972c57daf8fc2737e4",
+            "fc1c2a9b23ca98460bfe0dc95242989f5e325388f964b9caf4a0ccc5",
+        ],
+    ),
+    (
+        Mode::Sha3_256,
+        [
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            "5d53469f20fef4f8eab52b88044ede69c77a6a68a60728609fc4a65ff531e7d0",
+            "fded8fd9d6551c601eeb3b7c6bc5e5cfd8aad1d015b7e9aaa9c9b9475231d5e2",
+            "cf3ccff92480a29160c2d38317c430e14749bfee1788106957dfe73f8c4930e5",
+            "ce9d7dc90913ee5d92745019479a5352c6d6279bef18ed07dc0a83ee8084daca",
+            "7ea02960ce8fbfe84aee4c418ee7197f9b337493ed0ff9a369c0393c5909a4dc",
+        ],
+    ),
+    (
+        Mode::Sha3_384,
+        [
+            "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+            "127677f8b66725bbcb7c3eae9698351ca41e0eb6d66c784bd28dcdb3b5fb12d0c8e840342db03ad1ae180b92e3504933",
+            "1f91ee551ad18f268876d1fc262f137fe196580216c5193819a95ec5222537d2a658dd129c3d8080e65ec7460f1f4704",
+            "5b8d0d5cf8b41be507be8fcbfcbdbac3a28eb368d430fed6780aaa78a93a8da4a6c50485949ca344f228be91a96005a3",
+            "4a2f0a8f2f1f4cc4605cc2537e0be28cf8b465c30f0a54b494a7128ec54ee4e85706b5e47a5697344d15cbf85680cd40",
+            "9cd523da0e12bc9e274d3f7eea948516a0e1a5a5589de4afcf9fcbfc7feaf7c9a1d6d560c4a4b097981c360138d8d462",
+        ],
+    ),
+    (
+        Mode::Sha3_512,
+        [
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+            "7127aab211f82a18d06cf7578ff49d5089017944139aa60d8bee057811a15fb55a53887600a3eceba004de51105139f32506fe5b53e1913bfa6b32e716fe97da",
+            "3ccc850d53a1287af7b4560b2ef0d43eb5d9a80d62a0e9cf1dbc040135921104d4395168e90bfc871773ebb34bca1bd67056e1cc7dc7a48ff7c3167d389f117c",
+            "5d63f2bbe971a983ac6847480106e4e1264ee3a0befd79954914e1d86e795b2e18238f12fc5e46cb9cc78efdec610a93647cc04e1c23d8caaa6a58c21dd26c07",
+            "921d9b7b2b0f3066a1646dbb058c979cb3925dec0f8c269faaa7f9648e73465ae55ec527257d5d5e1cfdbf5d6799bea1004b6186f5108c74e3b92fe924166558",
+            "50650a6eb488b5c815dd1c66a023797894c298c7471a0221985cc854f069d8ffb031a32c5cb61f7e953f04e92f4ae1a283bf00c9cbcc70221eb937ac48ec0745",
+        ],
+    ),
+    (
+        Mode::Shake128,
+        [
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+            "0b784469a0628e03861cd8a196dfafa0e9e8056d04cddcc49f0746b9ad43ccb2",
+            "1e552791cc4e93a0d4a8dc47ae49228c2faa869e40e628f6ace477aec3f1ca7a",
+            "f15277eb61c4908d44a2853f3cde071ae2ed7a23461fbe162a1a98cf6875059c",
+            "015be3338c986d9846affa0f94b4afc2a76bc289c709e1a596ec9eccf090a773",
+            "d1540342d5d0c4ac2c50f9f64ca35d381fde29796a4cda61233307c954cde49a",
+        ],
+    ),
+    (
+        Mode::Shake256,
+        [
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+            "b8d01df855f7075882c636f6ddeacf41e5de0bbf30042ef0a86e36f4b8600d546c516501a6a3c821678d3d9943fa9e74b9b99fccd47aecc91dd1f4946b8355b3",
+            "c45dae624ad8a2f5aa7bac9d7557737fd91c96eedb70a6be5574d57a844eade07f4056bf081a1098101cea8132188c422136feb4687d1e2209f3fd28bedfb8f4",
+            "b7ff4073b3f5a8eabd6e17705ca7f6761a31058f9df781a6a47e3a3063b9d67a757e8dbf043dac48d2154e46d59c0b9e8bc36ba035153691fbe83b9eff5dae4a",
+            "01d90952c642a5eb2a8fc9d713f843a45d7ac05132dddcb2efc9bebc27e37bcbe42130c36f3540250ab11796980e773683f28d07f0f838606fb9c45e452bd38f",
+            "f728d10b65e9d233da2305b4cc761b2deb2070ce25ee67c63483f7487f46874cad47226c6dd2025fb605ff35ca0c72343ea63120aca167a2f77e6984abfd33f4",
+        ],
+    ),
+    (
+        Mode::Keccak256,
+        [
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+            "cbdfd9dee5faad3818d6b06f95a219fd290b0e1706f6a82e5a595b9ce9faca62",
+            "7ce759f1ab7f9ce437719970c26b0a66ff11fe3e38e17df89cf5d29c7d7f807e",
+            "ac73d4fae68b8453f764007c1a20ce95994187861f0c3227a3a8e99a73a3b1db",
+            "7db13cef7fffd48b7462e9288e10e9e041589368e44bd140d1873d7290dc8029",
+        ],
+    ),
+    (
+        Mode::Keccak512,
+        [
+            "0eab42de4c3ceb9235fc91acffe746b29c29a8c366b7c60e4e67c466f36a4304c00fa9caf9d87976ba469bcbe06713b435f091ef2769fb160cdab33d3670680e",
+            "40f0a44b4452c44baf401b49411f861caac716ba87be7d6894757f1114fcec44a4d4a9f44bcab569fabc676e761fe9d097dd191d5d9c71d66250b3e867071553",
+            "fe0953f9afdffed7ff9764c2590ff0e6af1b0689e42ddca68d6ef003ddce2671b806e0d2e6d57117bb75ad6166e2e990ca662b6a7f8945584f5308459eabae15",
+            "76fa23369085405345fe6a2831f334113bee6b111056e21072082af56e7c1ab4458858dbdb5f88e0d86d38ca654310c9a30712319c1f4f9783fe9f3ac0469527",
+            "e417b9573c871d948d48f62f6b16ea6cd1f1557a462ff5c1ae276d14d2fb43cd7084631656bf60f4ceb881133113d304335bd93487e8ec3e845ebc3c1877ca12",
+            "4d132fb6c8e2ee56034bc1fdd3940a0d899685dcb77cba5c56782e50a96d9af6ed00e2b8260da697052a9ff8cef4a33f6215725042b590dacb943633c6dc3cb3",
+        ],
+    ),
+    (
+        Mode::CShake128,
+        [
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+            "0b784469a0628e03861cd8a196dfafa0e9e8056d04cddcc49f0746b9ad43ccb2",
+            "1e552791cc4e93a0d4a8dc47ae49228c2faa869e40e628f6ace477aec3f1ca7a",
+            "f15277eb61c4908d44a2853f3cde071ae2ed7a23461fbe162a1a98cf6875059c",
+            "015be3338c986d9846affa0f94b4afc2a76bc289c709e1a596ec9eccf090a773",
+            "d1540342d5d0c4ac2c50f9f64ca35d381fde29796a4cda61233307c954cde49a",
+        ],
+    ),
+    (
+        Mode::CShake256,
+        [
+            "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be",
+            "b8d01df855f7075882c636f6ddeacf41e5de0bbf30042ef0a86e36f4b8600d546c516501a6a3c821678d3d9943fa9e74b9b99fccd47aecc91dd1f4946b8355b3",
+            "c45dae624ad8a2f5aa7bac9d7557737fd91c96eedb70a6be5574d57a844eade07f4056bf081a1098101cea8132188c422136feb4687d1e2209f3fd28bedfb8f4",
+            "b7ff4073b3f5a8eabd6e17705ca7f6761a31058f9df781a6a47e3a3063b9d67a757e8dbf043dac48d2154e46d59c0b9e8bc36ba035153691fbe83b9eff5dae4a",
+            "01d90952c642a5eb2a8fc9d713f843a45d7ac05132dddcb2efc9bebc27e37bcbe42130c36f3540250ab11796980e773683f28d07f0f838606fb9c45e452bd38f",
+            "f728d10b65e9d233da2305b4cc761b2deb2070ce25ee67c63483f7487f46874cad47226c6dd2025fb605ff35ca0c72343ea63120aca167a2f77e6984abfd33f4",
+        ],
+    ),
+    (
+        Mode::RawShake128,
+        [
+            "fa019a3b17630df6014853b5470773f13c3ab704478211d7a65867515dea1cc7",
+            "d9e247ab6e375eda0493ea9baa528c12f44091be293b4b492d23211b1e0b8bf8",
+            "d4044a338766bd755270452ae4e4c9d463bed25e426652cd048d78a1a92a8027",
+            "b611c32b107f490a1149abd8471537815da4b1e9b798136fdcfe0e9b6bd55322",
+            "2060c1d6cb026f511f121ea84427870eeb52ba6d241879eddbe6bc5f7d10a960",
+            "e6b709536ba715e785af26bef0cf624b3f8a262452dbfd58ed3ea0d31e84c7c0",
+        ],
+    ),
+    (
+        Mode::RawShake256,
+        [
+            "3a1108d4a90a31b85a10bdce77f4bfbdcc5b1d70dd405686f8bbde834aa1a410db8c9e1c166c3e239cd76a55f6a692aa2d1749f2ec79cd0ba3b17bb659959b6e",
+            "04517aa66b262753282831b94dc5ea3985f52bf42d9101a26845b027474621da9fa450da503d82757086e3931cb78f5dc56df42bb7af151289f8da4049735192",
+            "282f36d2344383c9c48f853712a58b2bbd788c88bdb9edf9125fe44fd191c65d6b600200d979ca11f65caa804f76a4e6df4a4bd42ad0f4f8f694733baf33ec36",
+            "cf0f7f96df769c9df63672ff68b09f6c3d2700fbdcecaca3c9351a311b410352448a552b5d347b2d3e022cd649e729949ba70474369856e4124aa2583bfe976b",
+            "3f0a502c4c448c89195937179ef1cde8137102803713839884f9641edf9024df809e2e83e6097c84680149b682c9a898164bb6ccd3ec882bec923135c5bccfc5",
+            "968dea5afe882351852f9a5df5119cb2f59274c8b3238ba6c002270070f0038fcc384a74e6212d266b027f6834efcee41e3eb7c9dec3d7e6d10cfa413aa3a88e",
+        ],
+    ),
+];
+
+// writes `data` to a file of its own in the temp directory and returns the path
+#[cfg(feature = "std")]
+fn temp_file(label: &str, data: &[u8]) -> String {
+    let path = env::temp_dir().join(format!("sha3sum-test-{}-{}", process::id(), label));
+    fs::write(&path, data).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn known_answers() {
+    sha3sum::run_test();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn files_match_reference_digests() {
+    for (mode, digests) in REFERENCE {
+        for (len, expected) in sizes(mode).into_iter().zip(digests) {
+            let path = temp_file(&format!("{}-{}", mode, len), &pattern(len));
+
+            let mut sponge = Sponge::new(mode);
+            sponge.absorb(&path).unwrap();
+            let digest = sponge.squeeze();
+            fs::remove_file(&path).unwrap();
+
+            assert_eq!(digest, expected, "{} of {} bytes", mode, len);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn in_memory_paths_match_reference_digests() {
+    for (mode, digests) in REFERENCE {
+        for (len, expected) in sizes(mode).into_iter().zip(digests) {
+            let data = pattern(len);
+
+            let mut sponge = Sponge::new(mode);
+            sponge.absorb_bytes(&data);
+            assert_eq!(
+                sponge.squeeze(),
+                expected,
+                "absorb_bytes, {} of {} bytes",
+                mode,
+                len
+            );
+
+            #[cfg(feature = "std")]
+            {
+                let mut sponge = Sponge::new(mode);
+                sponge.absorb_reader(data.as_slice()).unwrap();
+                assert_eq!(
+                    sponge.squeeze(),
+                    expected,
+                    "absorb_reader, {} of {} bytes",
+                    mode,
+                    len
+                );
+            }
+
+            let mut sponge = Sponge::new(mode);
+            for piece in data.chunks(mode.rate() - 3) {
+                sponge.update(piece);
+            }
+            assert_eq!(
+                sha3sum::to_hex(&sponge.finalize()),
+                expected,
+                "update, {} of {} bytes",
+                mode,
+                len
+            );
+        }
+    }
+}
+
+// pieces that straddle block boundaries every which way, empty ones included
+#[cfg(feature = "alloc")]
+#[test]
+fn uneven_updates_hash_like_one_absorb_bytes() {
+    for mode in MODES {
+        let rate = mode.rate();
+        let data = pattern(10 * rate + 7);
+
+        let mut one_call = Sponge::new(mode);
+        one_call.absorb_bytes(&data);
+
+        let mut sponge = Sponge::new(mode);
+        let mut rest = data.as_slice();
+        for len in [1, 7, 0, rate - 1, rate, 2 * rate + 3, 13]
+            .into_iter()
+            .cycle()
+        {
+            if rest.is_empty() {
+                break;
+            }
+            let (piece, tail) = rest.split_at(len.min(rest.len()));
+            sponge.update(piece);
+            rest = tail;
+        }
+        // padding whatever is still buffered
+        sponge.absorb_bytes(&[]);
+
+        assert_eq!(sponge.squeeze(), one_call.squeeze(), "{}", mode);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// however the input got in and the output gets out, the sponge is padded exactly once
+#[cfg(feature = "alloc")]
+#[test]
+fn sponge_is_padded_exactly_once() {
+    for mode in MODES {
+        let data = pattern(mode.rate() + 5);
+
+        let mut one_call = Sponge::new(mode);
+        one_call.absorb_bytes(&data);
+        let expected = one_call.squeeze();
+
+        let mut sponge = Sponge::new(mode);
+        sponge.absorb_bytes(&data);
+        assert_eq!(
+            hex(&sponge.finalize()),
+            expected,
+            "absorb_bytes then finalize, {}",
+            mode
+        );
+
+        let mut sponge = Sponge::new(mode);
+        sponge.update(&data);
+        assert_eq!(
+            hex(&sponge.finalize()),
+            expected,
+            "update then finalize, {}",
+            mode
+        );
+
+        let mut sponge = Sponge::new(mode);
+        sponge.update(&data);
+        assert_eq!(sponge.squeeze(), expected, "update then squeeze, {}", mode);
+        assert_eq!(sponge.squeeze(), expected, "second squeeze, {}", mode);
+
+        let mut sponge = Sponge::new(mode);
+        sponge.update(&data);
+        let mut out = vec![0; expected.len() / 2];
+        sponge.squeeze_into(&mut out);
+        assert_eq!(hex(&out), expected, "squeeze_into, {}", mode);
+    }
+}
+
+// `finalize_into` and `squeeze_into` are all that is left without `alloc`
+#[test]
+fn fixed_size_output_matches_reference_digests() {
+    for (mode, digests) in REFERENCE {
+        for (len, expected) in sizes(mode).into_iter().zip(digests) {
+            let data = pattern(len);
+            let hex = |digest: &[u8]| -> String {
+                digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+            };
+
+            let mut digest = [0; 64];
+            let digest = &mut digest[..mode.output_len()];
+
+            let mut sponge = Sponge::new(mode);
+            sponge.absorb_bytes(&data);
+            sponge.squeeze_into(digest);
+            assert_eq!(
+                hex(digest),
+                expected,
+                "squeeze_into, {} of {} bytes",
+                mode,
+                len
+            );
+
+            let mut sponge = Sponge::new(mode);
+            for piece in data.chunks(mode.rate() + 5) {
+                sponge.update(piece);
+            }
+            sponge.finalize_into(digest);
+            assert_eq!(
+                hex(digest),
+                expected,
+                "finalize_into, {} of {} bytes",
+                mode,
+                len
+            );
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reset_sponge_hashes_the_next_file_from_scratch() {
+    let (mode, digests) = REFERENCE[1];
+    let first = temp_file("reset-first", &pattern(1000));
+    let second = temp_file("reset-second", &pattern(mode.rate()));
+
+    let fresh = |path: &String| {
+        let mut sponge = Sponge::new(mode);
+        sponge.absorb(path).unwrap();
+        sponge.squeeze()
+    };
+
+    let mut sponge = Sponge::new(mode);
+    sponge.absorb(&first).unwrap();
+    let first_digest = sponge.squeeze();
+    sponge.reset();
+    sponge.absorb(&second).unwrap();
+    let second_digest = sponge.squeeze();
+
+    assert_eq!(first_digest, fresh(&first));
+    assert_eq!(second_digest, fresh(&second));
+    fs::remove_file(&first).unwrap();
+    fs::remove_file(&second).unwrap();
+
+    assert_eq!(second_digest, digests[3]);
+}
+
+#[test]
+fn squeeze_array_of_the_empty_string() {
+    const EXPECTED: [u8; 32] = [
+        0xa7, 0xff, 0xc6, 0xf8, 0xbf, 0x1e, 0xd7, 0x66, 0x51, 0xc1, 0x47, 0x56, 0xa0, 0x61, 0xd6,
+        0x62, 0xf5, 0x80, 0xff, 0x4d, 0xe4, 0x3b, 0x49, 0xfa, 0x82, 0xd8, 0x0a, 0x4b, 0x80, 0xf8,
+        0x43, 0x4a,
+    ];
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_bytes(b"");
+    let digest: [u8; 32] = sponge.squeeze_array();
+    assert_eq!(digest, EXPECTED);
+}
+
+#[test]
+#[should_panic(expected = "output buffer must be 32 bytes")]
+fn squeeze_array_of_the_wrong_length_panics() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    let _: [u8; 64] = sponge.squeeze_array();
+}
+
+// the first and last 32 bytes of 512 bytes of output, from the NIST SHAKE128 examples
+#[cfg(feature = "alloc")]
+#[test]
+fn shake128_matches_the_nist_examples() {
+    let cases: [(&[u8], &str, &str); 2] = [
+        (
+            b"",
+            "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+            "43e41b45a653f2a5c4492c1add544512dda2529833462b71a41a45be97290b6f",
+        ),
+        (
+            &[0xa3; 200],
+            "131ab8d2b594946b9c81333f9bb6e0ce75c3b93104fa3469d3917457385da037",
+            "44c9fb359fd56ac0a9a75a743cff6862f17d7259ab075216c0699511643b6439",
+        ),
+    ];
+
+    for (message, first, last) in cases {
+        let mut sponge = Sponge::new(Mode::Shake128);
+        sponge.absorb_bytes(message);
+        let output = sponge.squeeze_xof(512);
+
+        assert_eq!(hex(&output[..32]), first);
+        assert_eq!(hex(&output[480..]), last);
+    }
+}
+
+// output lengths on either side of one squeezed block, where the sponge has to permute again;
+// each is a prefix of the longest, whose ends come from Python's hashlib
+#[cfg(feature = "alloc")]
+#[test]
+fn squeeze_xof_lengths_around_the_rate() {
+    let data = b"squeeze me";
+    for (mode, first, last) in [
+        (
+            Mode::Shake128,
+            "515d31ace0ca3308eec312fd35f14e88f3f7758a55f54aa6382c4a73552cb238",
+            "16f0f9693d76b6ddd3ea702f10b8b1b9c70b4aee029d42411ca23dc0f286a51a",
+        ),
+        (
+            Mode::Shake256,
+            "8d006f0f2000fcaa336eec939a427fc7ad8923f31095647864546560125a0d05",
+            "596914c9fb5492752b7622bb67acf9927401371bee7ef6fe6113ec155f0b6275",
+        ),
+    ] {
+        let rate = mode.rate();
+        let mut sponge = Sponge::new(mode);
+        sponge.update(data);
+        let longest = sponge.squeeze_xof(3 * rate + 5);
+        assert_eq!(hex(&longest[..32]), first, "{}", mode);
+        assert_eq!(hex(&longest[longest.len() - 32..]), last, "{}", mode);
+
+        for len in [0, 1, rate - 1, rate, rate + 1, 2 * rate] {
+            let mut sponge = Sponge::new(mode);
+            sponge.update(data);
+            assert_eq!(
+                sponge.squeeze_xof(len),
+                longest[..len],
+                "{} bytes of {}",
+                len,
+                mode
+            );
+        }
+    }
+}
+
+// the original Keccak padding, as Ethereum uses it
+#[cfg(feature = "alloc")]
+#[test]
+fn keccak256_known_answers() {
+    for (message, expected) in [
+        (
+            &b""[..],
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        ),
+        (
+            b"abc",
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        ),
+    ] {
+        let mut sponge = Sponge::new(Mode::Keccak256);
+        sponge.absorb_bytes(message);
+        assert_eq!(sponge.squeeze(), expected);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn keccak512_known_answers() {
+    for (message, expected) in [
+        (
+            &b""[..],
+            "0eab42de4c3ceb9235fc91acffe746b29c29a8c366b7c60e4e67c466f36a4304\
+             c00fa9caf9d87976ba469bcbe06713b435f091ef2769fb160cdab33d3670680e",
+        ),
+        (
+            b"abc",
+            "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5\
+             d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96",
+        ),
+    ] {
+        let mut sponge = Sponge::new(Mode::Keccak512);
+        sponge.absorb_bytes(message);
+        assert_eq!(sponge.squeeze(), expected);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reset_keeps_the_cshake_customization() {
+    let fresh = || Sponge::new_cshake(Mode::CShake128, b"name", b"custom").unwrap();
+
+    let mut sponge = fresh();
+    sponge.update(&pattern(500));
+    sponge.reset();
+    sponge.update(b"abc");
+
+    let mut expected = fresh();
+    expected.update(b"abc");
+
+    assert_eq!(sponge.finalize(), expected.finalize());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn finalized_sponge_refuses_more_input() {
+    use std::io::Write;
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_bytes(b"abc");
+    let digest = sponge.squeeze_bytes();
+
+    assert!(matches!(
+        sponge.try_update(b"d"),
+        Err(Sha3Error::AlreadyFinalized)
+    ));
+    assert!(matches!(
+        sponge.absorb_reader(&b"d"[..]),
+        Err(Sha3Error::AlreadyFinalized)
+    ));
+    assert!(matches!(
+        sponge.absorb_readers([&b"d"[..]]),
+        Err(Sha3Error::AlreadyFinalized)
+    ));
+    assert!(sponge.write(b"d").is_err());
+    assert_eq!(sponge.squeeze_bytes(), digest);
+
+    sponge.reset();
+    sponge.try_update(b"abc").unwrap();
+    assert_eq!(sponge.squeeze_bytes(), digest);
+}
+
+#[test]
+#[should_panic(expected = "already finalized")]
+fn update_after_finalizing_panics() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    let _: [u8; 32] = sponge.squeeze_array();
+    sponge.update(b"abc");
+}
+
+// the wipe that runs on drop leaves nothing behind: a customized sponge ends up exactly like a
+// fresh one with the same parameters and no customization
+#[cfg(all(feature = "zeroize", feature = "alloc"))]
+#[test]
+fn zeroize_wipes_the_state_buffer_and_reset_point() {
+    let mut sponge = Sponge::new_cshake(Mode::CShake128, b"name", b"custom").unwrap();
+    sponge.update(&pattern(500));
+    assert_ne!(sponge.state(), &[0; 25]);
+
+    sponge.zeroize();
+    assert_eq!(sponge.state(), &[0; 25]);
+    assert_eq!(
+        sponge.snapshot(),
+        Sponge::with_params(168, 32, 0x04, true).unwrap().snapshot()
+    );
+
+    sponge.reset();
+    assert_eq!(sponge.state(), &[0; 25]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn missing_and_unnamed_files_are_errors() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+
+    assert!(matches!(
+        sponge.absorb(&String::new()),
+        Err(Sha3Error::EmptyInput)
+    ));
+    assert!(matches!(
+        sponge.absorb(&String::from("/nonexistent/sha3sum-test")),
+        Err(Sha3Error::Io(_))
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn all_modes_lists_each_mode_once_by_a_name_it_parses() {
+    let modes: Vec<Mode> = sha3sum::all_modes().collect();
+    assert_eq!(modes.len(), REFERENCE.len());
+
+    for (mode, _) in REFERENCE {
+        assert_eq!(modes.iter().filter(|&&listed| listed == mode).count(), 1);
+    }
+    for mode in modes {
+        assert_eq!(Mode::try_from(mode.to_string().as_str()).unwrap(), mode);
+    }
+
+    assert_eq!(Mode::try_from("SHAKE128").unwrap(), Mode::Shake128);
+    assert_eq!(Mode::try_from("384").unwrap(), Mode::Sha3_384);
+    assert!(Mode::try_from("sha3-128").is_err());
+}
+
+// a sponge built from nothing but the mode's rate, length and suffix hashes like the mode itself
+#[cfg(feature = "alloc")]
+#[test]
+fn suffix_is_all_that_separates_the_modes() {
+    for (mode, digests) in REFERENCE {
+        let data = pattern(sizes(mode)[2]);
+
+        let mut sponge =
+            Sponge::with_params(mode.rate(), mode.output_len(), mode.suffix(), mode.is_xof())
+                .unwrap();
+        sponge.update(&data);
+        assert_eq!(sha3sum::to_hex(&sponge.finalize()), digests[2], "{}", mode);
+    }
+
+    assert_eq!(Mode::Sha3_256.suffix(), 0x06);
+    assert_eq!(Mode::Shake128.suffix(), 0x1f);
+    assert_eq!(Mode::Keccak512.suffix(), 0x01);
+    assert_eq!(Mode::RawShake256.suffix(), 0x07);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn with_params_checks_its_parameters() {
+    assert!(Sponge::with_params(0, 32, 0x06, false).is_err());
+    assert!(Sponge::with_params(100, 32, 0x06, false).is_err());
+    assert!(Sponge::with_params(200, 32, 0x06, false).is_err());
+    assert!(Sponge::with_params(136, 32, 0x00, false).is_err());
+    assert!(Sponge::with_params(136, 32, 0x80, false).is_err());
+
+    // only an XOF sponge squeezes lengths other than its own
+    let mut shake = Sponge::with_params(168, 32, 0x1f, true).unwrap();
+    shake.update(b"abc");
+    assert_eq!(shake.squeeze_xof(100)[..32], shake.squeeze_bytes()[..]);
+    let mut out = [0; 100];
+    shake.squeeze_into(&mut out);
+
+    let fixed = std::panic::catch_unwind(|| {
+        let mut out = [0; 100];
+        Sponge::with_params(136, 32, 0x06, false)
+            .unwrap()
+            .squeeze_into(&mut out);
+    });
+    assert!(fixed.is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn multi_sponge_matches_each_mode_alone() {
+    let modes: Vec<Mode> = REFERENCE.iter().map(|&(mode, _)| mode).collect();
+
+    for len in [0, 1, 71, 72, 137, 168, 169, 300_000] {
+        let data = pattern(len);
+
+        let mut sponges = MultiSponge::new(&modes);
+        sponges.update(b"discarded by the reset");
+        sponges.reset();
+        for piece in data.chunks(1000) {
+            sponges.update(piece);
+        }
+
+        for (digest, &mode) in sponges.finalize().iter().zip(&modes) {
+            assert_eq!(
+                digest,
+                &sha3sum::digest(mode, &data),
+                "{} of {} bytes",
+                mode,
+                len
+            );
+        }
+
+        #[cfg(feature = "std")]
+        {
+            let mut sponges = MultiSponge::new(&modes).with_buffer_size(1000);
+            assert_eq!(sponges.buffer_size(), 1000);
+            let mut absorbed = 0;
+            sponges
+                .absorb_reader_with_progress(data.as_slice(), |done| {
+                    assert!(done > absorbed);
+                    absorbed = done;
+                })
+                .unwrap();
+            assert_eq!(absorbed, len as u64);
+
+            for (digest, &mode) in sponges.finalize().iter().zip(&modes) {
+                assert_eq!(
+                    digest,
+                    &sha3sum::digest(mode, &data),
+                    "absorb_reader_with_progress, {} of {} bytes",
+                    mode,
+                    len
+                );
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn modes_serialize_by_name() {
+    for (mode, _) in REFERENCE {
+        let json = serde_json::to_string(&mode).unwrap();
+        assert_eq!(json, format!("\"{}\"", mode));
+        assert_eq!(serde_json::from_str::<Mode>(&json).unwrap(), mode);
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Config {
+        algorithm: Mode,
+    }
+
+    let config: Config = serde_json::from_str(r#"{"algorithm": "sha3-512"}"#).unwrap();
+    assert_eq!(config.algorithm, Mode::Sha3_512);
+
+    let error = serde_json::from_str::<Mode>("\"md5\"").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid value: string \"md5\", expected a mode name")
+    );
+    assert!(serde_json::from_str::<Mode>("1").is_err());
+}
@@ -0,0 +1,510 @@
+// the reader, file and mmap paths, all of which need `std`
+#![cfg(feature = "std")]
+
+mod common;
+
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    process, thread,
+};
+
+use common::pattern;
+use sha3sum::{Mode, Sponge};
+
+const MODES: [Mode; 6] = [
+    Mode::Sha3_224,
+    Mode::Sha3_256,
+    Mode::Sha3_384,
+    Mode::Sha3_512,
+    Mode::Shake128,
+    Mode::Keccak512,
+];
+
+// lengths around one and two blocks of `mode`, plus enough for several double-buffered chunks
+fn lengths(mode: Mode) -> Vec<usize> {
+    let rate = mode.rate();
+    vec![0, 1, rate - 1, rate, rate + 1, 2 * rate, 300_000]
+}
+
+fn one_shot(mode: Mode, data: &[u8]) -> Vec<u8> {
+    let mut sponge = Sponge::new(mode);
+    sponge.absorb_bytes(data);
+    sponge.squeeze_bytes()
+}
+
+// hands out at most a few bytes per read, a different number each time
+struct Jagged<'a> {
+    data: &'a [u8],
+    reads: usize,
+}
+
+impl Read for Jagged<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.reads += 1;
+        let count = buffer.len().min(self.reads % 97 + 1).min(self.data.len());
+        buffer[..count].copy_from_slice(&self.data[..count]);
+        self.data = &self.data[count..];
+        Ok(count)
+    }
+}
+
+// yields `remaining` bytes and then fails
+struct Failing {
+    remaining: usize,
+}
+
+impl Read for Failing {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Err(io::Error::other("read failed"));
+        }
+
+        let count = buffer.len().min(self.remaining);
+        buffer[..count].fill(1);
+        self.remaining -= count;
+        Ok(count)
+    }
+}
+
+// 1000 bytes is seven whole SHA3-256 blocks and 48 bytes over, so the padding starts mid-block;
+// nothing here is specific to one platform
+#[test]
+fn absorb_hashes_a_file_that_ends_mid_block() {
+    let path = env::temp_dir().join(format!("sha3sum-test-{}-partial", process::id()));
+    fs::write(&path, pattern(1000)).unwrap();
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    let result = sponge.absorb(&path.to_string_lossy().into_owned());
+    fs::remove_file(&path).unwrap();
+    result.unwrap();
+
+    assert_eq!(
+        sponge.squeeze(),
+        "48e66a01861d0eadaacdb7a6ae7db6b9ac79242ecced4154a9fbb33c4e3cc571"
+    );
+}
+
+// an empty file is absorbed as one block of nothing but padding
+#[test]
+fn absorb_hashes_an_empty_file() {
+    let path = env::temp_dir().join(format!("sha3sum-test-{}-empty", process::id()));
+    fs::write(&path, b"").unwrap();
+
+    let expected = [
+        "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+        "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a\
+         c3713831264adb47fb6bd1e058d5f004",
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+         15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+    ];
+
+    let mut digests = Vec::new();
+    for mode in MODES {
+        let mut sponge = Sponge::new(mode);
+        let result = sponge.absorb(&path.to_string_lossy().into_owned());
+        digests.push(result.map(|()| sponge.squeeze()));
+    }
+    fs::remove_file(&path).unwrap();
+
+    for ((mode, digest), expected) in MODES.into_iter().zip(digests).zip(expected) {
+        assert_eq!(digest.unwrap(), expected, "{}", mode);
+    }
+}
+
+// a file of whole blocks still ends with a block of pure padding
+#[test]
+fn files_of_whole_blocks_match_one_shot() {
+    for mode in MODES {
+        for blocks in 1..=3 {
+            let data = pattern(blocks * mode.rate());
+            let path = env::temp_dir().join(format!(
+                "sha3sum-test-{}-blocks-{}-{}",
+                process::id(),
+                mode,
+                blocks
+            ));
+            fs::write(&path, &data).unwrap();
+
+            let mut sponge = Sponge::new(mode);
+            let result = sponge.absorb(&path.to_string_lossy().into_owned());
+            fs::remove_file(&path).unwrap();
+            result.unwrap();
+
+            let digest = sponge.squeeze_bytes();
+            assert_eq!(
+                digest,
+                one_shot(mode, &data),
+                "{} blocks of {}",
+                blocks,
+                mode
+            );
+            if mode == Mode::Sha3_256 && blocks == 1 {
+                assert_eq!(
+                    sponge.squeeze(),
+                    "cf3ccff92480a29160c2d38317c430e14749bfee1788106957dfe73f8c4930e5"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn cursor_hashes_like_the_file() {
+    let data = pattern(1000);
+    let path = env::temp_dir().join(format!("sha3sum-test-{}-cursor", process::id()));
+    fs::write(&path, &data).unwrap();
+
+    for mode in MODES {
+        let mut from_file = Sponge::new(mode);
+        let result = from_file.absorb(&path.to_string_lossy().into_owned());
+
+        let mut from_cursor = Sponge::new(mode);
+        from_cursor.absorb_reader(io::Cursor::new(&data)).unwrap();
+
+        result.unwrap();
+        assert_eq!(from_cursor.squeeze(), from_file.squeeze(), "{}", mode);
+    }
+    fs::remove_file(&path).unwrap();
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_reader(io::Cursor::new(b"abc")).unwrap();
+    assert_eq!(
+        sponge.squeeze(),
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+    );
+}
+
+// whatever `update` has buffered is absorbed ahead of the input every other absorb call takes
+#[test]
+fn io_copy_into_a_sponge_hashes_like_absorb_bytes() {
+    for mode in MODES {
+        for len in lengths(mode) {
+            let data = pattern(len);
+
+            let mut sponge = Sponge::new(mode);
+            let copied = io::copy(&mut io::Cursor::new(&data), &mut sponge).unwrap();
+            assert_eq!(copied, len as u64);
+
+            assert_eq!(
+                sponge.finalize(),
+                one_shot(mode, &data),
+                "{} of {}",
+                mode,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn short_reads_hash_like_one_shot() {
+    for mode in MODES {
+        for len in lengths(mode) {
+            let data = pattern(len);
+
+            let mut sponge = Sponge::new(mode);
+            sponge
+                .absorb_reader(Jagged {
+                    data: &data,
+                    reads: 0,
+                })
+                .unwrap();
+
+            assert_eq!(
+                sponge.squeeze_bytes(),
+                one_shot(mode, &data),
+                "{} of {}",
+                mode,
+                len
+            );
+        }
+    }
+}
+
+// a pipe has no size to read up front and hands out whatever the writer has put in so far
+#[test]
+fn pipe_hashes_like_one_shot() {
+    let data = pattern(300_000);
+    let (reader, mut writer) = io::pipe().unwrap();
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for piece in data.chunks(4099) {
+                writer.write_all(piece).unwrap();
+            }
+            drop(writer);
+        });
+
+        sponge.absorb_reader(reader).unwrap();
+    });
+
+    assert_eq!(sponge.squeeze_bytes(), one_shot(Mode::Sha3_256, &data));
+}
+
+#[test]
+fn double_buffered_hashes_like_one_shot() {
+    for mode in MODES {
+        for buffer_size in [1, mode.rate(), 1000, 64 * 1024] {
+            for len in lengths(mode) {
+                let data = pattern(len);
+
+                let mut sponge = Sponge::new(mode).with_buffer_size(buffer_size);
+                sponge
+                    .absorb_reader_double_buffered(Jagged {
+                        data: &data,
+                        reads: 0,
+                    })
+                    .unwrap();
+
+                assert_eq!(
+                    sponge.squeeze_bytes(),
+                    one_shot(mode, &data),
+                    "{} of {} through {}-byte chunks",
+                    mode,
+                    len,
+                    buffer_size
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn double_buffered_reports_read_errors() {
+    for remaining in [0, 10, 70_000, 300_000] {
+        let mut sponge = Sponge::new(Mode::Sha3_256);
+        assert!(
+            sponge
+                .absorb_reader_double_buffered(Failing { remaining })
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn pending_update_is_hashed_first() {
+    for mode in MODES {
+        let rate = mode.rate();
+        let data = pattern(3 * rate + 10);
+        let expected = one_shot(mode, &data);
+
+        for split in [1, rate - 1, rate + 1] {
+            let (head, tail) = data.split_at(split);
+            let path = env::temp_dir().join(format!(
+                "sha3sum-test-{}-pending-{}-{}",
+                process::id(),
+                mode,
+                split
+            ));
+            fs::write(&path, tail).unwrap();
+
+            let mut sponge = Sponge::new(mode);
+            sponge.update(head);
+            sponge.absorb_bytes(tail);
+            assert_eq!(sponge.squeeze_bytes(), expected, "absorb_bytes in {}", mode);
+
+            let mut sponge = Sponge::new(mode);
+            sponge.update(head);
+            sponge.absorb_reader(tail).unwrap();
+            assert_eq!(
+                sponge.squeeze_bytes(),
+                expected,
+                "absorb_reader in {}",
+                mode
+            );
+
+            let mut sponge = Sponge::new(mode);
+            sponge.update(head);
+            let result = sponge.absorb(&path.to_string_lossy().into_owned());
+            assert_eq!(sponge.squeeze_bytes(), expected, "absorb in {}", mode);
+            result.unwrap();
+
+            #[cfg(feature = "mmap")]
+            {
+                let mut sponge = Sponge::new(mode);
+                sponge.update(head);
+                let result = sponge.absorb_mmap(&path);
+                assert_eq!(sponge.squeeze_bytes(), expected, "absorb_mmap in {}", mode);
+                result.unwrap();
+            }
+
+            fs::remove_file(&path).unwrap();
+        }
+    }
+}
+
+#[test]
+fn readers_hash_like_their_concatenation() {
+    for mode in MODES {
+        let rate = mode.rate();
+        let data = pattern(5 * rate + 3);
+
+        for splits in [
+            vec![],
+            vec![0],
+            vec![1, rate],
+            vec![rate - 1, 3 * rate, 3 * rate],
+        ] {
+            let mut parts = Vec::new();
+            let mut start = 0;
+            for end in splits.iter().copied().chain([data.len()]) {
+                parts.push(&data[start..end]);
+                start = end;
+            }
+
+            let mut sponge = Sponge::new(mode);
+            sponge.absorb_readers(parts.iter().copied()).unwrap();
+
+            assert_eq!(
+                sponge.squeeze_bytes(),
+                one_shot(mode, &data),
+                "{} split at {:?}",
+                mode,
+                splits
+            );
+        }
+    }
+
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_readers(Vec::<&[u8]>::new()).unwrap();
+    assert_eq!(sponge.squeeze_bytes(), one_shot(Mode::Sha3_256, b""));
+}
+
+#[test]
+fn limited_reader_hashes_like_a_truncated_copy() {
+    for mode in MODES {
+        let data = pattern(3 * mode.rate() + 10);
+
+        for limit in lengths(mode).into_iter().filter(|&len| len < data.len()) {
+            let mut reader = Jagged {
+                data: &data,
+                reads: 0,
+            };
+
+            let mut sponge = Sponge::new(mode);
+            sponge
+                .absorb_reader_limited(&mut reader, limit as u64)
+                .unwrap();
+
+            assert_eq!(
+                sponge.squeeze_bytes(),
+                one_shot(mode, &data[..limit]),
+                "{} limited to {}",
+                mode,
+                limit
+            );
+            assert_eq!(reader.data, &data[limit..], "{} limited to {}", mode, limit);
+        }
+
+        let mut sponge = Sponge::new(mode);
+        sponge
+            .absorb_reader_limited(data.as_slice(), u64::MAX)
+            .unwrap();
+        assert_eq!(sponge.squeeze_bytes(), one_shot(mode, &data));
+    }
+}
+
+#[test]
+fn progress_counts_every_byte_absorbed() {
+    for mode in MODES {
+        for len in lengths(mode) {
+            let data = pattern(len);
+            let mut reports = Vec::new();
+
+            let mut sponge = Sponge::new(mode);
+            sponge
+                .absorb_reader_with_progress(
+                    Jagged {
+                        data: &data,
+                        reads: 0,
+                    },
+                    |absorbed| reports.push(absorbed),
+                )
+                .unwrap();
+            assert_eq!(sponge.squeeze_bytes(), one_shot(mode, &data));
+
+            // one report per block, including the padded final one, each a block further on
+            let expected: Vec<u64> = (1..=len / mode.rate() + 1)
+                .map(|blocks| (blocks * mode.rate()).min(len) as u64)
+                .collect();
+            assert_eq!(reports, expected, "{} of {}", mode, len);
+        }
+    }
+}
+
+#[test]
+fn buffer_size_rounds_up_to_whole_blocks() {
+    let sponge = Sponge::new(Mode::Sha3_256);
+    assert_eq!(sponge.buffer_size(), 65552);
+
+    assert_eq!(sponge.with_buffer_size(1).buffer_size(), 136);
+    assert_eq!(
+        Sponge::new(Mode::Sha3_512)
+            .with_buffer_size(145)
+            .buffer_size(),
+        216
+    );
+}
+
+#[test]
+fn hex_output_matches_bytes() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_bytes(b"abc");
+
+    let bytes = sponge.squeeze_bytes();
+    let expected: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    assert_eq!(sponge.squeeze(), expected);
+    assert_eq!(sponge.squeeze_upper(), expected.to_uppercase());
+    assert_eq!(sha3sum::to_hex(&[0x00, 0x0f, 0xa0, 0xff]), "000fa0ff");
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mmap_hashes_like_one_shot() {
+    for mode in MODES {
+        for len in lengths(mode)
+            .into_iter()
+            .chain([8 * 1024 * mode.rate() + 5])
+        {
+            let data = pattern(len);
+            let path = env::temp_dir().join(format!(
+                "sha3sum-test-{}-mmap-{}-{}",
+                process::id(),
+                mode,
+                len
+            ));
+            fs::write(&path, &data).unwrap();
+
+            let mut sponge = Sponge::new(mode);
+            let result = sponge.absorb_mmap(&path);
+            fs::remove_file(&path).unwrap();
+            result.unwrap();
+
+            assert_eq!(
+                sponge.squeeze_bytes(),
+                one_shot(mode, &data),
+                "{} of {}",
+                mode,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn absorb_reads_the_whole_file() {
+    let data = pattern(200_000);
+    let path = env::temp_dir().join(format!("sha3sum-test-{}-paths", process::id()));
+    fs::write(&path, &data).unwrap();
+
+    let mut sponge = Sponge::new(Mode::Sha3_384).with_buffer_size(4096);
+    let result = sponge.absorb(&path.to_string_lossy().into_owned());
+    fs::remove_file(&path).unwrap();
+    result.unwrap();
+
+    assert_eq!(sponge.squeeze_bytes(), one_shot(Mode::Sha3_384, &data));
+}
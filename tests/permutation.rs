@@ -0,0 +1,89 @@
+// the bare permutation
+
+mod common;
+
+use common::pattern;
+use sha3sum::{Mode, Sponge, keccak_f1600, keccak_p1600};
+
+// Keccak-f[1600] applied once to the all-zero state, from the Keccak team's reference code
+const ZERO_STATE_PERMUTED: [u64; 25] = [
+    0xF1258F7940E1DDE7,
+    0x84D5CCF933C0478A,
+    0xD598261EA65AA9EE,
+    0xBD1547306F80494D,
+    0x8B284E056253D057,
+    0xFF97A42D7F8E6FD4,
+    0x90FEE5A0A44647C4,
+    0x8C5BDA0CD6192E76,
+    0xAD30A6F71B19059C,
+    0x30935AB7D08FFC64,
+    0xEB5AA93F2317D635,
+    0xA9A6E6260D712103,
+    0x81A57C16DBCF555F,
+    0x43B831CD0347C826,
+    0x01F22F1A11A5569F,
+    0x05E5635A21D9AE61,
+    0x64BEFEF28CC970F2,
+    0x613670957BC46611,
+    0xB87C5A554FD00ECB,
+    0x8C3EE88A1CCF32C8,
+    0x940C7922AE3A2614,
+    0x1841F924A2C509E4,
+    0x16F53526E70465C2,
+    0x75F644E97F30A13B,
+    0xEAF1FF7B5CECA249,
+];
+
+#[test]
+fn permutation_of_the_zero_state() {
+    let mut state = [0; 25];
+    keccak_f1600(&mut state);
+    assert_eq!(state[0], 0xF1258F7940E1DDE7);
+    assert_eq!(state, ZERO_STATE_PERMUTED);
+
+    let mut state = [0; 25];
+    keccak_p1600(&mut state, 24);
+    assert_eq!(state, ZERO_STATE_PERMUTED);
+
+    // no rounds at all leaves the state alone
+    keccak_p1600(&mut state, 0);
+    assert_eq!(state, ZERO_STATE_PERMUTED);
+}
+
+#[test]
+#[should_panic(expected = "at most 24 rounds")]
+fn more_than_24_rounds_panics() {
+    keccak_p1600(&mut [0; 25], 25);
+}
+
+// the lanes alone carry a hash in progress between sponges, as long as it stopped on a block
+// boundary with nothing buffered
+#[test]
+fn state_carries_over_to_a_new_sponge() {
+    for mode in [
+        Mode::Sha3_256,
+        Mode::Sha3_512,
+        Mode::Shake128,
+        Mode::Keccak256,
+    ] {
+        let data = pattern(5 * mode.rate() + 17);
+        let split = 3 * mode.rate();
+        let len = mode.output_len();
+
+        let mut expected = [0; 64];
+        let mut sponge = Sponge::new(mode);
+        sponge.update(&data);
+        sponge.finalize_into(&mut expected[..len]);
+
+        let mut first = Sponge::new(mode);
+        first.update(&data[..split]);
+
+        let mut second = Sponge::new(mode);
+        second.set_state(*first.state());
+        second.update(&data[split..]);
+        let mut digest = [0; 64];
+        second.finalize_into(&mut digest[..len]);
+
+        assert_eq!(digest[..len], expected[..len], "{}", mode);
+    }
+}
@@ -0,0 +1,44 @@
+#![cfg(all(feature = "alloc", not(target_arch = "wasm32")))]
+
+mod common;
+
+use common::reference::{MODES, reference};
+use proptest::prelude::*;
+use sha3sum::Sponge;
+
+// mostly short inputs, with enough reaching past two blocks of the smallest rate (72 bytes)
+// that multi-block absorbs get covered too
+fn message() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        3 => prop::collection::vec(any::<u8>(), 0..=200),
+        1 => prop::collection::vec(any::<u8>(), 0..=2000),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn one_shot_matches_sha3_crate(data in message()) {
+        for mode in MODES {
+            let mut sponge = Sponge::new(mode);
+            sponge.absorb_bytes(&data);
+            prop_assert_eq!(sponge.squeeze_bytes(), reference(mode, &data), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn split_update_matches_sha3_crate(data in message(), split in any::<prop::sample::Index>()) {
+        let split = split.index(data.len() + 1);
+        for mode in MODES {
+            let mut sponge = Sponge::new(mode);
+            sponge.update(&data[..split]);
+            sponge.update(&data[split..]);
+            prop_assert_eq!(
+                sponge.finalize(),
+                reference(mode, &data),
+                "{} split at {}",
+                mode,
+                split
+            );
+        }
+    }
+}
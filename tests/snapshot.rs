@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::pattern;
+use sha3sum::{Mode, Sponge, SpongeState};
+
+fn straight_through(mut sponge: Sponge, data: &[u8]) -> Vec<u8> {
+    sponge.update(data);
+    sponge.finalize()
+}
+
+// hashes the first `split` bytes, passes the snapshot through `store`, and finishes on a
+// sponge rebuilt from whatever comes back
+fn resumed(
+    mut sponge: Sponge,
+    data: &[u8],
+    split: usize,
+    store: impl Fn(SpongeState) -> SpongeState,
+) -> Vec<u8> {
+    sponge.update(&data[..split]);
+    let snapshot = store(sponge.snapshot());
+
+    let mut sponge = Sponge::from_snapshot(snapshot).unwrap();
+    sponge.update(&data[split..]);
+    sponge.finalize()
+}
+
+fn sponges() -> Vec<Sponge> {
+    vec![
+        Sponge::new(Mode::Sha3_256),
+        Sponge::new(Mode::Sha3_512).with_buffer_size(4096),
+        Sponge::new(Mode::Shake128),
+        Sponge::new(Mode::Keccak256),
+        Sponge::new_cshake(Mode::CShake256, b"name", b"custom").unwrap(),
+        Sponge::with_params(160, 48, 0x1f, true).unwrap(),
+    ]
+}
+
+#[test]
+fn resumed_sponge_matches_straight_through() {
+    let data = pattern(1000);
+    for index in 0..sponges().len() {
+        let expected = straight_through(sponges().swap_remove(index), &data);
+        for split in [0, 1, 71, 72, 500, 1000] {
+            let sponge = sponges().swap_remove(index);
+            assert_eq!(
+                resumed(sponge, &data, split, |snapshot| snapshot),
+                expected,
+                "sponge {} split at {}",
+                index,
+                split
+            );
+        }
+    }
+}
+
+#[test]
+fn snapshot_keeps_the_buffer_size() {
+    let sponge = Sponge::new(Mode::Sha3_256).with_buffer_size(4096);
+    let restored = Sponge::from_snapshot(sponge.snapshot()).unwrap();
+    assert_eq!(restored.buffer_size(), sponge.buffer_size());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialized_snapshot_resumes() {
+    let data = pattern(1000);
+    let through_json = |snapshot: SpongeState| {
+        let json = serde_json::to_string(&snapshot).unwrap();
+        serde_json::from_str(&json).unwrap()
+    };
+
+    for index in 0..sponges().len() {
+        let expected = straight_through(sponges().swap_remove(index), &data);
+        let sponge = sponges().swap_remove(index);
+        assert_eq!(resumed(sponge, &data, 500, through_json), expected);
+    }
+}
+
+#[test]
+fn finalized_snapshot_is_not_padded_again() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.absorb_bytes(&pattern(100));
+    let expected = sponge.squeeze_bytes();
+
+    let mut resumed = Sponge::from_snapshot(sponge.snapshot()).unwrap();
+    assert_eq!(resumed.squeeze_bytes(), expected);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn malformed_snapshots_are_rejected() {
+    let mut sponge = Sponge::new(Mode::Sha3_256);
+    sponge.update(b"abc");
+    let json = serde_json::to_value(sponge.snapshot()).unwrap();
+
+    let tampered = |field: &str, value: serde_json::Value| {
+        let mut json = json.clone();
+        json[field] = value;
+        Sponge::from_snapshot(serde_json::from_value(json).unwrap()).is_err()
+    };
+
+    assert!(tampered("rate", 100.into()));
+    assert!(tampered("rounds", 0.into()));
+    assert!(tampered("pending", vec![0; 136].into()));
+    assert!(!tampered("pending", vec![0; 135].into()));
+}
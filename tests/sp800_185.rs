@@ -0,0 +1,381 @@
+// the NIST SP 800-185 sample values for cSHAKE, KMAC, TupleHash and ParallelHash
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use sha3sum::{Mode, Sponge};
+
+// the 200-byte message of the longer samples, 0x00 through 0xC7
+fn counting(len: usize) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
+
+fn to_hex_upper(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+fn cshake(mode: Mode, data: &[u8], name: &[u8], custom: &[u8]) -> String {
+    let mut sponge = Sponge::new_cshake(mode, name, custom).unwrap();
+    sponge.update(data);
+    to_hex_upper(&sponge.finalize())
+}
+
+#[test]
+fn cshake_samples() {
+    let samples = [
+        (
+            Mode::CShake128,
+            4,
+            "C1C36925B6409A04F1B504FCBCA9D82B4017277CB5ED2B2065FC1D3814D5AAF5",
+        ),
+        (
+            Mode::CShake128,
+            200,
+            "C5221D50E4F822D96A2E8881A961420F294B7B24FE3D2094BAED2C6524CC166B",
+        ),
+        (
+            Mode::CShake256,
+            4,
+            "D008828E2B80AC9D2218FFEE1D070C48B8E4C87BFF32C9699D5B6896EEE0EDD1\
+             64020E2BE0560858D9C00C037E34A96937C561A74C412BB4C746469527281C8C",
+        ),
+        (
+            Mode::CShake256,
+            200,
+            "07DC27B11E51FBAC75BC7B3C1D983E8B4B85FB1DEFAF218912AC86430273091\
+             727F42B17ED1DF63E8EC118F04B23633C1DFB1574C8FB55CB45DA8E25AFB092BB",
+        ),
+    ];
+
+    for (mode, len, expected) in samples {
+        assert_eq!(
+            cshake(mode, &counting(len), b"", b"Email Signature"),
+            expected,
+            "{} of {} bytes",
+            mode,
+            len
+        );
+    }
+}
+
+// the samples all leave the function name empty, so a named one is checked against the `sha3`
+// crate's output; with neither string cSHAKE is plain SHAKE
+#[test]
+fn cshake_with_and_without_name_and_customization() {
+    let data = counting(200);
+    let cases = [
+        (
+            Mode::CShake128,
+            Mode::Shake128,
+            "47212691CA4728B4341F0E26A5FE4D17E400719DE441CC7319A4868F7EC3DA6C",
+        ),
+        (
+            Mode::CShake256,
+            Mode::Shake256,
+            "4C7292E3BDB500B756C1E473E31FC70EF58A154D3E970D83C9AF8AB3DC6AB19F\
+             BAEE6C3247F5FA8CFB40B7BB440D19D6724D714E6798472D3AA962B5FDAA1B4E",
+        ),
+    ];
+
+    for (mode, shake, named) in cases {
+        assert_eq!(cshake(mode, &data, b"KMAC", b"app"), named, "{}", mode);
+
+        let mut plain = Sponge::new(shake);
+        plain.update(&data);
+        assert_eq!(
+            cshake(mode, &data, b"", b""),
+            to_hex_upper(&plain.squeeze_xof(named.len() / 2)),
+            "{}",
+            mode
+        );
+    }
+}
+
+// the key of every KMAC sample, 0x40 through 0x5F
+fn key() -> Vec<u8> {
+    (0x40..0x60).collect()
+}
+
+// (data length, customization, output length, digest) for samples 1-3 of each function
+type KmacSample = (usize, &'static [u8], usize, &'static str);
+
+const KMAC128: [KmacSample; 3] = [
+    (
+        4,
+        b"",
+        32,
+        "E5780B0D3EA6F7D3A429C5706AA43A00FADBD7D49628839E3187243F456EE14E",
+    ),
+    (
+        4,
+        b"My Tagged Application",
+        32,
+        "3B1FBA963CD8B0B59E8C1A6D71888B7143651AF8BA0A7070C0979E2811324AA5",
+    ),
+    (
+        200,
+        b"My Tagged Application",
+        32,
+        "1F5B4E6CCA02209E0DCB5CA635B89A15E271ECC760071DFD805FAA38F9729230",
+    ),
+];
+
+const KMAC256: [KmacSample; 3] = [
+    (
+        4,
+        b"My Tagged Application",
+        64,
+        "20C570C31346F703C9AC36C61C03CB64C3970D0CFC787E9B79599D273A68D2F7\
+         F69D4CC3DE9D104A351689F27CF6F5951F0103F33F4F24871024D9C27773A8DD",
+    ),
+    (
+        200,
+        b"",
+        64,
+        "75358CF39E41494E949707927CEE0AF20A3FF553904C86B08F21CC414BCFD691\
+         589D27CF5E15369CBBFF8B9A4C2EB17800855D0235FF635DA82533EC6B759B69",
+    ),
+    (
+        200,
+        b"My Tagged Application",
+        64,
+        "B58618F71F92E1D56C1B8C55DDD7CD188B97B4CA4D99831EB2699A837DA2E4D9\
+         70FBACFDE50033AEA585F1A2708510C32D07880801BD182898FE476876FC8965",
+    ),
+];
+
+#[test]
+fn kmac_samples() {
+    type Kmac = fn(&[u8], &[u8], usize, &[u8]) -> Vec<u8>;
+    let functions: [(&str, Kmac, [KmacSample; 3]); 2] = [
+        ("KMAC128", sha3sum::kmac128, KMAC128),
+        ("KMAC256", sha3sum::kmac256, KMAC256),
+    ];
+
+    for (name, kmac, samples) in functions {
+        for (index, (len, custom, out_len, expected)) in samples.into_iter().enumerate() {
+            assert_eq!(
+                to_hex_upper(&kmac(&key(), &counting(len), out_len, custom)),
+                expected,
+                "{} sample {}",
+                name,
+                index + 1
+            );
+        }
+    }
+}
+
+// the output length is absorbed along with the data, so a shorter MAC is not a prefix of a
+// longer one
+#[test]
+fn kmac_output_depends_on_its_length() {
+    let data = counting(200);
+    let long = sha3sum::kmac128(&key(), &data, 100, b"app");
+    assert_ne!(sha3sum::kmac128(&key(), &data, 32, b"app"), long[..32]);
+}
+
+#[test]
+fn tuplehash_samples() {
+    let two: [&[u8]; 2] = [&[0x00, 0x01, 0x02], &[0x10, 0x11, 0x12, 0x13, 0x14, 0x15]];
+    let three: [&[u8]; 3] = [
+        two[0],
+        two[1],
+        &[0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, 0x28],
+    ];
+
+    type TupleHash = fn(&[&[u8]], usize, &[u8]) -> Vec<u8>;
+    type Sample<'a> = (TupleHash, &'a [&'a [u8]], &'a [u8], usize, &'a str);
+    let samples: [Sample; 6] = [
+        (
+            sha3sum::tuplehash128,
+            &two,
+            b"",
+            32,
+            "C5D8786C1AFB9B82111AB34B65B2C0048FA64E6D48E263264CE1707D3FFC8ED1",
+        ),
+        (
+            sha3sum::tuplehash128,
+            &two,
+            b"My Tuple App",
+            32,
+            "75CDB20FF4DB1154E841D758E24160C54BAE86EB8C13E7F5F40EB35588E96DFB",
+        ),
+        (
+            sha3sum::tuplehash128,
+            &three,
+            b"My Tuple App",
+            32,
+            "E60F202C89A2631EDA8D4C588CA5FD07F39E5151998DECCF973ADB3804BB6E84",
+        ),
+        (
+            sha3sum::tuplehash256,
+            &two,
+            b"",
+            64,
+            "CFB7058CACA5E668F81A12A20A2195CE97A925F1DBA3E7449A56F82201EC6073\
+             11AC2696B1AB5EA2352DF1423BDE7BD4BB78C9AED1A853C78672F9EB23BBE194",
+        ),
+        (
+            sha3sum::tuplehash256,
+            &two,
+            b"My Tuple App",
+            64,
+            "147C2191D5ED7EFD98DBD96D7AB5A11692576F5FE2A5065F3E33DE6BBA9F3AA1\
+             C4E9A068A289C61C95AAB30AEE1E410B0B607DE3620E24A4E3BF9852A1D4367E",
+        ),
+        (
+            sha3sum::tuplehash256,
+            &three,
+            b"My Tuple App",
+            64,
+            "45000BE63F9B6BFD89F54717670F69A9BC763591A4F05C50D68891A744BCC6E7\
+             D6D5B5E82C018DA999ED35B0BB49C9678E526ABD8E85C13ED254021DB9E790CE",
+        ),
+    ];
+
+    for (index, (tuplehash, tuples, custom, out_len, expected)) in samples.into_iter().enumerate() {
+        assert_eq!(
+            to_hex_upper(&tuplehash(tuples, out_len, custom)),
+            expected,
+            "sample {}",
+            index + 1
+        );
+    }
+}
+
+// every element is length-prefixed, so moving a byte across a boundary changes the digest
+#[test]
+fn tuplehash_keeps_element_boundaries() {
+    let split: [&[u8]; 2] = [b"ab", b"c"];
+    let moved: [&[u8]; 2] = [b"a", b"bc"];
+
+    assert_ne!(
+        sha3sum::tuplehash128(&split, 32, b""),
+        sha3sum::tuplehash128(&moved, 32, b"")
+    );
+    assert_ne!(
+        sha3sum::tuplehash256(&split, 64, b""),
+        sha3sum::tuplehash256(&moved, 64, b"")
+    );
+    assert_ne!(
+        sha3sum::tuplehash128(&[b"abc"], 32, b""),
+        sha3sum::tuplehash128(&split, 32, b"")
+    );
+}
+
+// `count` runs of `width` bytes, 0x00.., 0x10.. and so on, as in the ParallelHash samples
+fn runs(width: u8, count: u8) -> Vec<u8> {
+    (0..count)
+        .flat_map(|run| (0..width).map(move |byte| run * 0x10 + byte))
+        .collect()
+}
+
+#[test]
+fn parallelhash_samples() {
+    type ParallelHash = fn(&[u8], usize, usize, &[u8]) -> Vec<u8>;
+    // the function, the width and count of the runs of input, B, S, L and the digest
+    type Sample<'a> = (ParallelHash, (u8, u8), usize, &'a [u8], usize, &'a str);
+    let samples: [Sample; 6] = [
+        (
+            sha3sum::parallelhash128,
+            (8, 3),
+            8,
+            b"",
+            32,
+            "BA8DC1D1D979331D3F813603C67F72609AB5E44B94A0B8F9AF46514454A2B4F5",
+        ),
+        (
+            sha3sum::parallelhash128,
+            (8, 3),
+            8,
+            b"Parallel Data",
+            32,
+            "FC484DCB3F84DCEEDC353438151BEE58157D6EFED0445A81F165E495795B7206",
+        ),
+        (
+            sha3sum::parallelhash128,
+            (12, 6),
+            12,
+            b"Parallel Data",
+            32,
+            "F7FD5312896C6685C828AF7E2ADB97E393E7F8D54E3C2EA4B95E5ACA3796E8FC",
+        ),
+        (
+            sha3sum::parallelhash256,
+            (8, 3),
+            8,
+            b"",
+            64,
+            "BC1EF124DA34495E948EAD207DD9842235DA432D2BBC54B4C110E64C45110553\
+             1B7F2A3E0CE055C02805E7C2DE1FB746AF97A1DD01F43B824E31B87612410429",
+        ),
+        (
+            sha3sum::parallelhash256,
+            (8, 3),
+            8,
+            b"Parallel Data",
+            64,
+            "CDF15289B54F6212B4BC270528B49526006DD9B54E2B6ADD1EF6900DDA3963BB\
+             33A72491F236969CA8AFAEA29C682D47A393C065B38E29FAE651A2091C833110",
+        ),
+        (
+            sha3sum::parallelhash256,
+            (12, 6),
+            12,
+            b"Parallel Data",
+            64,
+            "69D0FCB764EA055DD09334BC6021CB7E4B61348DFF375DA262671CDEC3EFFA8D\
+             1B4568A6CCE16B1CAD946DDDE27F6CE2B8DEE4CD1B24851EBF00EB90D43813E9",
+        ),
+    ];
+
+    for (index, (parallelhash, (width, count), block_size, custom, out_len, expected)) in
+        samples.into_iter().enumerate()
+    {
+        assert_eq!(
+            to_hex_upper(&parallelhash(
+                &runs(width, count),
+                block_size,
+                out_len,
+                custom
+            )),
+            expected,
+            "sample {}",
+            index + 1
+        );
+    }
+}
+
+// however the blocks are shared out, including more threads than blocks and a last block
+// that is short, the digest is the one hashing them in turn gives
+#[test]
+fn threaded_parallelhash_matches_sequential() {
+    let data = common::pattern(100 * 1024 + 7);
+
+    for block_size in [1, 100, 8192] {
+        let sequential = sha3sum::parallelhash128_with_threads(&data, block_size, 32, b"", 1);
+        let wide = sha3sum::parallelhash256_with_threads(&data, block_size, 64, b"s", 1);
+
+        for threads in [2, 3, 8, 64, 1000] {
+            assert_eq!(
+                sha3sum::parallelhash128_with_threads(&data, block_size, 32, b"", threads),
+                sequential,
+                "{}-byte blocks on {} threads",
+                block_size,
+                threads
+            );
+            assert_eq!(
+                sha3sum::parallelhash256_with_threads(&data, block_size, 64, b"s", threads),
+                wide,
+                "{}-byte blocks on {} threads",
+                block_size,
+                threads
+            );
+        }
+
+        assert_eq!(
+            sha3sum::parallelhash128(&data, block_size, 32, b""),
+            sequential
+        );
+    }
+}
@@ -0,0 +1,232 @@
+// the TurboSHAKE and KangarooTwelve test vectors from RFC 9861
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::pattern;
+use sha3sum::{k12, turboshake128, turboshake256};
+
+fn to_hex_upper(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+// the last 32 of 10032 output bytes, which checks squeezing well past the first block
+fn tail(output: &[u8]) -> String {
+    to_hex_upper(&output[output.len() - 32..])
+}
+
+#[test]
+fn turboshake128_vectors() {
+    assert_eq!(
+        to_hex_upper(&turboshake128(b"", 0x1f, 32)),
+        "1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C"
+    );
+    assert_eq!(
+        to_hex_upper(&turboshake128(b"", 0x1f, 64)),
+        "1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C\
+         3E8CCAE2A4DAE56C84A04C2385C03C15E8193BDF58737363321691C05462C8DF"
+    );
+    assert_eq!(
+        tail(&turboshake128(b"", 0x1f, 10032)),
+        "A3B9B0385900CE761F22AED548E754DA10A5242D62E8C658E3F3A923A7555607"
+    );
+
+    // ptn(17^i) for i = 0 through 5
+    let patterns = [
+        "55CEDD6F60AF7BB29A4042AE832EF3F58DB7299F893EBB9247247D856958DAA9",
+        "9C97D036A3BAC819DB70EDE0CA554EC6E4C2A1A4FFBFD9EC269CA6A111161233",
+        "96C77C279E0126F7FC07C9B07F5CDAE1E0BE60BDBE10620040E75D7223A624D2",
+        "D4976EB56BCF118520582B709F73E1D6853E001FDAF80E1B13E0D0599D5FB372",
+        "DA67C7039E98BF530CF7A37830C6664E14CBAB7F540F58403B1B82951318EE5C",
+        "B97A906FBF83EF7C812517ABF3B2D0AEA0C4F60318CE11CF103925127F59EECD",
+    ];
+    for (power, expected) in patterns.into_iter().enumerate() {
+        let data = pattern(17usize.pow(power as u32));
+        assert_eq!(
+            to_hex_upper(&turboshake128(&data, 0x1f, 32)),
+            expected,
+            "ptn(17^{})",
+            power
+        );
+    }
+
+    // the other domain separation bytes
+    let domains: [(&[u8], u8, &str); 6] = [
+        (
+            &[0xff; 3],
+            0x01,
+            "BF323F940494E88EE1C540FE660BE8A0C93F43D15EC006998462FA994EED5DAB",
+        ),
+        (
+            &[0xff],
+            0x06,
+            "8EC9C66465ED0D4A6C35D13506718D687A25CB05C74CCA1E42501ABD83874A67",
+        ),
+        (
+            &[0xff; 3],
+            0x07,
+            "B658576001CAD9B1E5F399A9F77723BBA05458042D68206F7252682DBA3663ED",
+        ),
+        (
+            &[0xff; 7],
+            0x0b,
+            "8DEEAA1AEC47CCEE569F659C21DFA8E112DB3CEE37B18178B2ACD805B799CC37",
+        ),
+        (
+            &[0xff],
+            0x30,
+            "553122E2135E363C3292BED2C6421FA232BAB03DAA07C7D6636603286506325B",
+        ),
+        (
+            &[0xff; 3],
+            0x7f,
+            "16274CC656D44CEFD422395D0F9053BDA6D28E122ABA15C765E5AD0E6EAF26F9",
+        ),
+    ];
+    for (data, domain, expected) in domains {
+        assert_eq!(
+            to_hex_upper(&turboshake128(data, domain, 32)),
+            expected,
+            "D = {:#04x}",
+            domain
+        );
+    }
+}
+
+#[test]
+fn turboshake256_vectors() {
+    assert_eq!(
+        to_hex_upper(&turboshake256(b"", 0x1f, 64)),
+        "367A329DAFEA871C7802EC67F905AE13C57695DC2C6663C61035F59A18F8E7DB\
+         11EDC0E12E91EA60EB6B32DF06DD7F002FBAFABB6E13EC1CC20D995547600DB0"
+    );
+    assert_eq!(
+        to_hex_upper(&turboshake256(&pattern(1), 0x1f, 64)),
+        "3E1712F928F8EAF1054632B2AA0A246ED8B0C378728F60BC970410155C28820E\
+         90CC90D8A3006AA2372C5C5EA176B0682BF22BAE7467AC94F74D43D39B0482E2"
+    );
+}
+
+// TurboSHAKE with a domain byte of 0x1f is SHAKE with half the rounds, so it must differ from
+// SHAKE itself, and the domain byte must separate otherwise identical calls
+#[test]
+fn turboshake_domains_are_separate() {
+    let mut shake = sha3sum::Sponge::new(sha3sum::Mode::Shake128);
+    shake.absorb_bytes(b"abc");
+    assert_ne!(turboshake128(b"abc", 0x1f, 32), shake.squeeze_xof(32));
+    assert_ne!(
+        turboshake128(b"abc", 0x1f, 32),
+        turboshake128(b"abc", 0x1e, 32)
+    );
+}
+
+#[test]
+#[should_panic(expected = "domain byte must be in 0x01..=0x7f")]
+fn turboshake_rejects_a_zero_domain() {
+    turboshake128(b"", 0x00, 32);
+}
+
+#[test]
+fn k12_vectors() {
+    assert_eq!(
+        to_hex_upper(&k12(b"", b"", 32)),
+        "1AC2D450FC3B4205D19DA7BFCA1B37513C0803577AC7167F06FE2CE1F0EF39E5"
+    );
+    assert_eq!(
+        to_hex_upper(&k12(b"", b"", 64)),
+        "1AC2D450FC3B4205D19DA7BFCA1B37513C0803577AC7167F06FE2CE1F0EF39E5\
+         4269C056B8C82E48276038B6D292966CC07A3D4645272E31FF38508139EB0A71"
+    );
+    assert_eq!(
+        tail(&k12(b"", b"", 10032)),
+        "E8DC563642F7228C84684C898405D3A834799158C079B12880277A1D28E2FF6D"
+    );
+
+    // ptn(17^i) for i = 0 through 6; from 17^4 bytes on the input spans several 8192-byte
+    // chunks, so those go through the tree
+    let patterns = [
+        "2BDA92450E8B147F8A7CB629E784A058EFCA7CF7D8218E02D345DFAA65244A1F",
+        "6BF75FA2239198DB4772E36478F8E19B0F371205F6A9A93A273F51DF37122888",
+        "0C315EBCDEDBF61426DE7DCF8FB725D1E74675D7F5327A5067F367B108ECB67C",
+        "CB552E2EC77D9910701D578B457DDF772C12E322E4EE7FE417F92C758F0D59D0",
+        "8701045E22205345FF4DDA05555CBB5C3AF1A771C2B89BAEF37DB43D9998B9FE",
+        "844D610933B1B9963CBDEB5AE3B6B05CC7CBD67CEEDF883EB678A0A8E0371682",
+        "3C390782A8A4E89FA6367F72FEAAF13255C8D95878481D3CD8CE85F58E880AF8",
+    ];
+    for (power, expected) in patterns.into_iter().enumerate() {
+        let data = pattern(17usize.pow(power as u32));
+        assert_eq!(
+            to_hex_upper(&k12(&data, b"", 32)),
+            expected,
+            "ptn(17^{})",
+            power
+        );
+    }
+
+    // customization strings, with the message empty or a few 0xFF bytes
+    let customized: [(usize, usize, &str); 4] = [
+        (
+            0,
+            1,
+            "FAB658DB63E94A246188BF7AF69A133045F46EE984C56E3C3328CAAF1AA1A583",
+        ),
+        (
+            1,
+            41,
+            "D848C5068CED736F4462159B9867FD4C20B808ACC3D5BC48E0B06BA0A3762EC4",
+        ),
+        (
+            3,
+            41 * 41,
+            "C389E5009AE57120854C2E8C64670AC01358CF4C1BAF89447A724234DC7CED74",
+        ),
+        (
+            7,
+            41 * 41 * 41,
+            "75D2F86A2E644566726B4FBCFC5657B9DBCF070C7B0DCA06450AB291D7443BCF",
+        ),
+    ];
+    for (message_len, custom_len, expected) in customized {
+        assert_eq!(
+            to_hex_upper(&k12(&vec![0xff; message_len], &pattern(custom_len), 32)),
+            expected,
+            "{} bytes of 0xFF with ptn({})",
+            message_len,
+            custom_len
+        );
+    }
+
+    // either side of one chunk, counting the customization string and its length encoding
+    // as part of the input
+    let boundaries: [(usize, usize, &str); 4] = [
+        (
+            8191,
+            0,
+            "1B577636F723643E990CC7D6A659837436FD6A103626600EB8301CD1DBE553D6",
+        ),
+        (
+            8192,
+            0,
+            "48F256F6772F9EDFB6A8B661EC92DC93B95EBD05A08A17B39AE3490870C926C3",
+        ),
+        (
+            8192,
+            8189,
+            "3ED12F70FB05DDB58689510AB3E4D23C6C6033849AA01E1D8C220A297FEDCD0B",
+        ),
+        (
+            8192,
+            8190,
+            "6A7C1B6A5CD0D8C9CA943A4A216CC64604559A2EA45F78570A15253D67BA00AE",
+        ),
+    ];
+    for (message_len, custom_len, expected) in boundaries {
+        assert_eq!(
+            to_hex_upper(&k12(&pattern(message_len), &pattern(custom_len), 32)),
+            expected,
+            "ptn({}) with ptn({})",
+            message_len,
+            custom_len
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// run with `wasm-pack test --node --features wasm`
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use sha3sum::{
+    Mode, Sponge,
+    wasm::{WasmSponge, sha3_256_hex},
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const ABC_SHA3_256: &str = "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532";
+
+// drives a sponge from JavaScript the way a page would, with bytes from TextEncoder, hashing
+// the text twice to check that taking the digest reset the sponge; returns both digests
+#[wasm_bindgen(inline_js = "
+export function hash_text_twice(sponge, text) {
+    const bytes = new TextEncoder().encode(text);
+    const digests = [];
+    for (let round = 0; round < 2; round++) {
+        sponge.update(bytes.subarray(0, 1));
+        sponge.update(bytes.subarray(1));
+        digests.push(sponge.hex_digest());
+    }
+    sponge.free();
+    return digests.join(' ');
+}
+")]
+extern "C" {
+    fn hash_text_twice(sponge: WasmSponge, text: &str) -> String;
+}
+
+fn sponge(mode: &str) -> WasmSponge {
+    WasmSponge::new(mode).unwrap_or_else(|_| panic!("{} is a mode", mode))
+}
+
+#[wasm_bindgen_test]
+fn one_shot_hex() {
+    assert_eq!(sha3_256_hex(b"abc"), ABC_SHA3_256);
+}
+
+#[wasm_bindgen_test]
+fn sponge_driven_from_javascript() {
+    assert_eq!(
+        hash_text_twice(sponge("sha3-256"), "abc"),
+        format!("{} {}", ABC_SHA3_256, ABC_SHA3_256)
+    );
+}
+
+#[wasm_bindgen_test]
+fn streaming_digest_matches_one_shot() {
+    let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    for mode in [
+        Mode::Sha3_224,
+        Mode::Sha3_512,
+        Mode::Shake128,
+        Mode::Keccak256,
+    ] {
+        let mut streaming = sponge(&mode.to_string());
+        for piece in data.chunks(100) {
+            streaming.update(piece);
+        }
+
+        let mut one_shot = Sponge::new(mode);
+        one_shot.absorb_bytes(&data);
+        assert_eq!(streaming.digest(), one_shot.squeeze_bytes(), "{}", mode);
+    }
+}
+
+#[wasm_bindgen_test]
+fn unknown_mode_throws() {
+    assert!(WasmSponge::new("md5").is_err());
+}